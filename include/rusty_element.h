@@ -0,0 +1,350 @@
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The continuity of a finite element
+ */
+enum Continuity {
+  Continuous = 0,
+  Discontinuous = 1,
+};
+typedef uint8_t Continuity;
+
+enum DTYPE {
+  /**
+   * 32 bit float
+   */
+  Float32 = 0,
+  /**
+   * 64 bit float
+   */
+  Float64 = 1,
+  /**
+   * 8 bit signed integer
+   */
+  Int8 = 2,
+  /**
+   * 32 bit signed integer
+   */
+  Int32 = 3,
+  /**
+   * 64 bit signed integer
+   */
+  Int64 = 4,
+  /**
+   * 8 bit unsigned integer
+   */
+  Unsigned8 = 5,
+  /**
+   * 32 bit unsigned integer
+   */
+  Unsigned32 = 6,
+  /**
+   * 64 bit unsigned integer
+   */
+  Unsigned64 = 7,
+  /**
+   * Machine dependent indexing type
+   */
+  Usize = 8,
+};
+typedef uint8_t DTYPE;
+
+enum ElementFamily {
+  Lagrange = 0,
+  RaviartThomas = 1,
+  NedelecFirstKind = 2,
+  BrezziDouglasMarini = 3,
+  NedelecSecondKind = 4,
+  Regge = 5,
+  HellanHerrmannJohnson = 6,
+};
+typedef uint8_t ElementFamily;
+
+enum MUTABILITY {
+  NotMutable = 0,
+  Mutable = 1,
+};
+typedef uint8_t MUTABILITY;
+
+enum MapType {
+  Identity = 0,
+  CovariantPiola = 1,
+  ContravariantPiola = 2,
+  L2Piola = 3,
+  DoubleCovariantPiola = 4,
+  DoubleContravariantPiola = 5,
+};
+typedef uint8_t MapType;
+
+enum OWNERSHIP {
+  NotOwner = 0,
+  Owner = 1,
+};
+typedef uint8_t OWNERSHIP;
+
+enum ReferenceCellType {
+  Interval = 0,
+  Triangle = 1,
+  Quadrilateral = 2,
+  Tetrahedron = 3,
+  Hexahedron = 4,
+  Prism = 5,
+  Pyramid = 6,
+};
+typedef uint8_t ReferenceCellType;
+
+typedef struct ElementContainer ElementContainer;
+
+typedef struct ReferenceCellContainer ReferenceCellContainer;
+
+/**
+ * A data container for communication with a C ABI.
+ *
+ * The basic task of this container is to provide a C ABI
+ * compatible type to store arbitrary data arrays, and
+ * to convert them back and forth into corresponding Rust types.
+ */
+typedef struct RustyDataContainer RustyDataContainer;
+
+/**
+ * New cell container from interval.
+ */
+struct ReferenceCellContainer *reference_cell_container_new_from_interval(void);
+
+/**
+ * New cell container from triangle.
+ */
+struct ReferenceCellContainer *reference_cell_container_new_from_triangle(void);
+
+/**
+ * New cell container from quadrilateral.
+ */
+struct ReferenceCellContainer *reference_cell_container_new_from_quadrilateral(void);
+
+/**
+ * New cell container from tetrahedron.
+ */
+struct ReferenceCellContainer *reference_cell_container_new_from_tetrahedron(void);
+
+/**
+ * New cell container from Hexahedron.
+ */
+struct ReferenceCellContainer *reference_cell_container_new_from_hexahedron(void);
+
+/**
+ * New cell container from Prism.
+ */
+struct ReferenceCellContainer *reference_cell_container_new_from_prism(void);
+
+/**
+ * New cell container from Pyramid.
+ */
+struct ReferenceCellContainer *reference_cell_container_new_from_pyramid(void);
+
+/**
+ * Destroy a cell container.
+ */
+void reference_cell_container_destroy(struct ReferenceCellContainer*);
+
+/**
+ * Get dimension.
+ */
+size_t reference_cell_container_get_dim(struct ReferenceCellContainer *cell_container);
+
+struct RustyDataContainer *reference_cell_container_get_vertices(struct ReferenceCellContainer *cell_container);
+
+struct RustyDataContainer *reference_cell_container_get_edges(struct ReferenceCellContainer *cell_container);
+
+struct RustyDataContainer *reference_cell_container_get_faces(struct ReferenceCellContainer *cell_container);
+
+struct RustyDataContainer *reference_cell_container_get_faces_nvertices(struct ReferenceCellContainer *cell_container);
+
+uintptr_t reference_cell_container_get_entity_count(uintptr_t dim,
+                                                    struct ReferenceCellContainer *cell_container);
+
+uintptr_t reference_cell_container_get_vertex_count(struct ReferenceCellContainer *cell_container);
+
+uintptr_t reference_cell_container_get_edge_count(struct ReferenceCellContainer *cell_container);
+
+uintptr_t reference_cell_container_get_face_count(struct ReferenceCellContainer *cell_container);
+
+uintptr_t reference_cell_container_get_volume_count(struct ReferenceCellContainer *cell_container);
+
+struct RustyDataContainer *reference_cell_container_get_connectivity(uintptr_t entity_dim,
+                                                                     uintptr_t entity_number,
+                                                                     uintptr_t connected_dim,
+                                                                     struct ReferenceCellContainer *cell_container);
+
+ReferenceCellType reference_cell_container_get_cell_type(struct ReferenceCellContainer *cell_container);
+
+/**
+ * New element container.
+ *
+ * Returns a null pointer if the element is not supported.
+ */
+struct ElementContainer *element_container_new(ElementFamily family,
+                                               ReferenceCellType cell_type,
+                                               size_t degree,
+                                               Continuity continuity);
+
+/**
+ * Destroy an element container.
+ */
+void element_container_destroy(struct ElementContainer*);
+
+/**
+ * Get the number of basis functions.
+ */
+size_t element_container_get_dim(struct ElementContainer *element_container);
+
+/**
+ * Get the value size.
+ */
+size_t element_container_get_value_size(struct ElementContainer *element_container);
+
+/**
+ * Get the degree.
+ */
+size_t element_container_get_degree(struct ElementContainer *element_container);
+
+/**
+ * Get the highest degree of a polynomial in the element's polynomial set.
+ */
+size_t element_container_get_highest_degree(struct ElementContainer *element_container);
+
+ElementFamily element_container_get_family(struct ElementContainer *element_container);
+
+ReferenceCellType element_container_get_cell_type(struct ElementContainer *element_container);
+
+MapType element_container_get_map_type(struct ElementContainer *element_container);
+
+bool element_container_get_discontinuous(struct ElementContainer *element_container);
+
+struct RustyDataContainer *element_container_get_entity_dofs(uintptr_t entity_dim,
+                                                             uintptr_t entity_number,
+                                                             struct ElementContainer *element_container);
+
+struct RustyDataContainer *element_container_get_entity_closure_dofs(uintptr_t entity_dim,
+                                                                     uintptr_t entity_number,
+                                                                     struct ElementContainer *element_container);
+
+/**
+ * Get the number of derivatives that are tabulated when tabulating up to order `nderivs`.
+ */
+uintptr_t element_container_get_derivative_count(uintptr_t nderivs,
+                                                 struct ElementContainer *element_container);
+
+/**
+ * Tabulate the basis functions and their derivatives at a set of points.
+ *
+ * The points must be a container of f64 values, with the first tdim values giving the first
+ * point, the next tdim the second point, and so on. The returned container has shape
+ * (derivative count, number of points, dim, value size).
+ */
+struct RustyDataContainer *element_container_tabulate(struct RustyDataContainer *points,
+                                                      uintptr_t nderivs,
+                                                      struct ElementContainer *element_container);
+
+/**
+ * Get the points at which a function is evaluated to compute its DOF values.
+ *
+ * The first tdim values of the returned container give the first point, the next tdim the
+ * second point, and so on.
+ */
+struct RustyDataContainer *element_container_get_interpolation_points(struct ElementContainer *element_container);
+
+/**
+ * Get the interpolation matrix, which maps the values of a function at the interpolation points
+ * to its DOF values.
+ *
+ * The returned container has shape (dim, value size, number of points).
+ */
+struct RustyDataContainer *element_container_get_interpolation_matrix(struct ElementContainer *element_container);
+
+/**
+ * Destroy a data container.
+ */
+void rusty_data_container_destroy(struct RustyDataContainer*);
+
+/**
+ * Create a new f32 data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_f32(size_t nitems);
+
+/**
+ * Create a new f64 data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_f64(size_t nitems);
+
+/**
+ * Create a new u8 data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_u8(size_t nitems);
+
+/**
+ * Create a new u32 data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_u32(size_t nitems);
+
+/**
+ * Create a new u64 data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_u64(size_t nitems);
+
+/**
+ * Create a new i8 data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_i8(size_t nitems);
+
+/**
+ * Create a new i32 data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_i32(size_t nitems);
+
+/**
+ * Create a new i64 data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_i64(size_t nitems);
+
+/**
+ * Create a new usize data container.
+ */
+struct RustyDataContainer *rusty_data_container_new_usize(size_t nitems);
+
+/**
+ * Get nitems
+ */
+size_t rusty_data_container_get_nitems(struct RustyDataContainer *ptr);
+
+/**
+ * Get itemsize
+ */
+size_t rusty_data_container_get_itemsize(struct RustyDataContainer *ptr);
+
+/**
+ * Get dtype
+ */
+DTYPE rusty_data_container_get_dtype(struct RustyDataContainer *ptr);
+
+/**
+ * Get is_owner
+ */
+OWNERSHIP rusty_data_container_get_is_owner(struct RustyDataContainer *ptr);
+
+/**
+ * Get is_mutable
+ */
+MUTABILITY rusty_data_container_get_is_mutable(struct RustyDataContainer *ptr);
+
+/**
+ * Get data
+ */
+void *rusty_data_container_get_data(struct RustyDataContainer *ptr);
+
+struct RustyDataContainer *new_from_pointer(void *ptr,
+                                            size_t nitems,
+                                            DTYPE dtype,
+                                            MUTABILITY is_mutable);
@@ -1,3 +1,10 @@
 """rusty_element - Element definitions in Rust."""
 
 from .reference_cell import ReferenceCell
+from .finite_element import (
+    FiniteElement,
+    ElementFamily,
+    ReferenceCellType,
+    MapType,
+    Continuity,
+)
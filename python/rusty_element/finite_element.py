@@ -0,0 +1,185 @@
+"""Finite element definitions."""
+
+from enum import IntEnum
+
+import numpy as np
+import rusty_cffi
+from .config import get_lib, get_ffi
+
+
+class ReferenceCellType(IntEnum):
+    """Reference cell types."""
+
+    Interval = 0
+    Triangle = 1
+    Quadrilateral = 2
+    Tetrahedron = 3
+    Hexahedron = 4
+    Prism = 5
+    Pyramid = 6
+
+
+class ElementFamily(IntEnum):
+    """Element families."""
+
+    Lagrange = 0
+    RaviartThomas = 1
+    NedelecFirstKind = 2
+    BrezziDouglasMarini = 3
+    NedelecSecondKind = 4
+    Regge = 5
+    HellanHerrmannJohnson = 6
+
+
+class MapType(IntEnum):
+    """Map types."""
+
+    Identity = 0
+    CovariantPiola = 1
+    ContravariantPiola = 2
+    L2Piola = 3
+    DoubleCovariantPiola = 4
+    DoubleContravariantPiola = 5
+
+
+class Continuity(IntEnum):
+    """Element continuity."""
+
+    Continuous = 0
+    Discontinuous = 1
+
+
+class FiniteElement:
+    """A finite element."""
+
+    def __init__(self, ptr):
+        """A new finite element from a C pointer."""
+        self._ptr = ptr
+
+    @classmethod
+    def create(cls, family, cell_type, degree, continuity=Continuity.Continuous):
+        """Create an element of the given family, cell type and degree."""
+        ptr = get_lib().element_container_new(
+            int(family), int(cell_type), degree, int(continuity)
+        )
+        if ptr == get_ffi().NULL:
+            raise ValueError("Unsupported element.")
+        return cls(ptr)
+
+    @property
+    def dim(self):
+        """Number of basis functions."""
+        return get_lib().element_container_get_dim(self._ptr)
+
+    @property
+    def value_size(self):
+        """Number of components of the values of the basis functions."""
+        return get_lib().element_container_get_value_size(self._ptr)
+
+    @property
+    def degree(self):
+        """Degree."""
+        return get_lib().element_container_get_degree(self._ptr)
+
+    @property
+    def highest_degree(self):
+        """Highest degree of a polynomial in the element's polynomial set."""
+        return get_lib().element_container_get_highest_degree(self._ptr)
+
+    @property
+    def family(self):
+        """Element family."""
+        return ElementFamily(get_lib().element_container_get_family(self._ptr))
+
+    @property
+    def cell_type(self):
+        """Cell type."""
+        return ReferenceCellType(get_lib().element_container_get_cell_type(self._ptr))
+
+    @property
+    def map_type(self):
+        """Map type."""
+        return MapType(get_lib().element_container_get_map_type(self._ptr))
+
+    @property
+    def discontinuous(self):
+        """Whether the element is discontinuous."""
+        return get_lib().element_container_get_discontinuous(self._ptr)
+
+    def entity_dofs(self, entity_dim, entity_number):
+        """DOFs associated with a sub-entity."""
+        if entity_dim > 3:
+            raise ValueError(f"`entity_dim` = {entity_dim}, but `entity_dim` < 4 required.")
+        container = rusty_cffi.RustyDataContainer(
+            get_lib().element_container_get_entity_dofs(entity_dim, entity_number, self._ptr)
+        )
+        return container.data.copy()
+
+    def entity_closure_dofs(self, entity_dim, entity_number):
+        """DOFs associated with the closure of a sub-entity."""
+        if entity_dim > 3:
+            raise ValueError(f"`entity_dim` = {entity_dim}, but `entity_dim` < 4 required.")
+        container = rusty_cffi.RustyDataContainer(
+            get_lib().element_container_get_entity_closure_dofs(
+                entity_dim, entity_number, self._ptr
+            )
+        )
+        return container.data.copy()
+
+    @property
+    def tdim(self):
+        """Topological dimension of the reference cell."""
+        return {
+            ReferenceCellType.Interval: 1,
+            ReferenceCellType.Triangle: 2,
+            ReferenceCellType.Quadrilateral: 2,
+        }.get(self.cell_type, 3)
+
+    @property
+    def interpolation_points(self):
+        """Points at which a function is evaluated to compute its DOF values.
+
+        The returned array has shape (number of points, tdim).
+        """
+        container = rusty_cffi.RustyDataContainer(
+            get_lib().element_container_get_interpolation_points(self._ptr)
+        )
+        return container.data.copy().reshape(-1, self.tdim)
+
+    @property
+    def interpolation_matrix(self):
+        """Matrix that maps the values of a function at the interpolation points to its DOF values.
+
+        The returned array has shape (dim, value size * number of points), with column
+        v * (number of points) + p corresponding to component v at point p.
+        """
+        container = rusty_cffi.RustyDataContainer(
+            get_lib().element_container_get_interpolation_matrix(self._ptr)
+        )
+        return container.data.copy().reshape(self.dim, -1)
+
+    def tabulate(self, points, nderivs):
+        """Tabulate the basis functions and their derivatives at a set of points.
+
+        The points array has shape (number of points, tdim); a 1D array is interpreted as the
+        concatenated coordinates of the points. The returned array has shape (number of
+        derivatives, number of points, dim, value size).
+        """
+        points = np.ascontiguousarray(points, dtype=np.float64)
+        if points.ndim == 1:
+            points = points.reshape(-1, self.tdim)
+        if points.ndim != 2 or points.shape[1] != self.tdim:
+            raise ValueError("Points must have shape (number of points, tdim).")
+        npoints = points.shape[0]
+        points_container = rusty_cffi.RustyDataContainer.from_array(points.reshape(-1))
+        container = rusty_cffi.RustyDataContainer(
+            get_lib().element_container_tabulate(points_container.c_ptr, nderivs, self._ptr)
+        )
+        nderiv_count = get_lib().element_container_get_derivative_count(nderivs, self._ptr)
+        return container.data.copy().reshape(
+            nderiv_count, npoints, self.dim, self.value_size
+        )
+
+    def __del__(self):
+        """Call destructor."""
+        get_lib().element_container_destroy(self._ptr)
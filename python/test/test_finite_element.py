@@ -0,0 +1,78 @@
+import numpy as np
+import pytest
+from rusty_element import FiniteElement, ElementFamily, ReferenceCellType, MapType, Continuity
+
+
+def test_lagrange_triangle():
+    e = FiniteElement.create(ElementFamily.Lagrange, ReferenceCellType.Triangle, 1)
+    assert e.dim == 3
+    assert e.value_size == 1
+    assert e.degree == 1
+    assert e.map_type == MapType.Identity
+    assert e.family == ElementFamily.Lagrange
+    assert e.cell_type == ReferenceCellType.Triangle
+    assert not e.discontinuous
+    for v in range(3):
+        assert np.array_equal(e.entity_dofs(0, v), [v])
+    assert np.array_equal(e.entity_closure_dofs(1, 0), [1, 2])
+    assert np.array_equal(e.entity_closure_dofs(2, 0), [0, 1, 2])
+    assert np.allclose(e.interpolation_points, [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]])
+    assert np.allclose(e.interpolation_matrix, np.eye(3))
+
+    points = np.array([[0.2, 0.3], [1.0, 0.0]])
+    table = e.tabulate(points, 1)
+    assert table.shape == (3, 2, 3, 1)
+    assert np.allclose(table[0, :, :, 0], [[0.5, 0.2, 0.3], [0.0, 1.0, 0.0]])
+    assert np.allclose(table[1, 0, :, 0], [-1.0, 1.0, 0.0])
+    assert np.allclose(table[2, 0, :, 0], [-1.0, 0.0, 1.0])
+
+
+@pytest.mark.parametrize(
+    "family,cell_type,degree,dim,value_size,map_type",
+    [
+        (ElementFamily.RaviartThomas, ReferenceCellType.Triangle, 2, 8, 2, MapType.ContravariantPiola),
+        (ElementFamily.NedelecFirstKind, ReferenceCellType.Tetrahedron, 1, 6, 3, MapType.CovariantPiola),
+        (ElementFamily.RaviartThomas, ReferenceCellType.Quadrilateral, 1, 4, 2, MapType.ContravariantPiola),
+    ],
+)
+def test_vector_elements(family, cell_type, degree, dim, value_size, map_type):
+    e = FiniteElement.create(family, cell_type, degree)
+    assert e.dim == dim
+    assert e.value_size == value_size
+    assert e.map_type == map_type
+    tdim = 2 if cell_type != ReferenceCellType.Tetrahedron else 3
+    table = e.tabulate(np.full((4, tdim), 0.2), 0)
+    assert table.shape == (1, 4, dim, value_size)
+
+
+def test_discontinuous():
+    e = FiniteElement.create(
+        ElementFamily.Lagrange, ReferenceCellType.Interval, 2, Continuity.Discontinuous
+    )
+    assert e.discontinuous
+    assert len(e.entity_dofs(0, 0)) == 0
+    assert np.array_equal(e.entity_dofs(1, 0), [0, 1, 2])
+
+
+@pytest.mark.parametrize(
+    "family,cell_type,degree",
+    [
+        (ElementFamily.RaviartThomas, ReferenceCellType.Prism, 1),
+        (ElementFamily.Regge, ReferenceCellType.Quadrilateral, 1),
+        (ElementFamily.RaviartThomas, ReferenceCellType.Triangle, 0),
+    ],
+)
+def test_unsupported_element(family, cell_type, degree):
+    with pytest.raises(ValueError):
+        FiniteElement.create(family, cell_type, degree)
+
+
+def test_tabulate_points_shape():
+    e = FiniteElement.create(ElementFamily.Lagrange, ReferenceCellType.Triangle, 1)
+    table = e.tabulate(np.array([0.2, 0.3, 1.0, 0.0]), 0)
+    assert table.shape == (1, 2, 3, 1)
+    assert np.allclose(table[0, :, :, 0], [[0.5, 0.2, 0.3], [0.0, 1.0, 0.0]])
+    with pytest.raises(ValueError):
+        e.tabulate(np.array([[0.2, 0.3, 0.1]]), 0)
+    with pytest.raises(ValueError):
+        e.tabulate(np.array([0.2, 0.3, 0.1]), 0)
@@ -1,6 +1,9 @@
 //! C Interface
 
 use crate::cell::*;
+use crate::element::*;
+use crate::map::MapType;
+use crate::polyset::derivative_count;
 use libc::size_t;
 pub use rusty_cffi::RustyDataContainer;
 
@@ -148,12 +151,7 @@ pub extern "C" fn reference_cell_container_get_connectivity(
 ) -> Box<RustyDataContainer> {
     let cell = get_reference(cell_container);
     let connectivity = cell.connectivity(entity_dim, entity_number, connected_dim);
-
-    let vec = match connectivity {
-        Ok(v) => v,
-        Err(()) => Vec::<usize>::new(),
-    };
-    RustyDataContainer::from_vec(vec).to_box()
+    RustyDataContainer::from_vec(connectivity.unwrap_or_default()).to_box()
 }
 
 #[no_mangle]
@@ -162,3 +160,237 @@ pub extern "C" fn reference_cell_container_get_cell_type(
 ) -> ReferenceCellType {
     get_reference(cell_container).cell_type()
 }
+
+pub struct ElementContainer(Box<dyn FiniteElement>);
+
+impl ElementContainer {
+    pub fn to_box(self) -> Box<ElementContainer> {
+        Box::new(self)
+    }
+}
+
+fn get_element(element_container: Option<Box<ElementContainer>>) -> &'static dyn FiniteElement {
+    Box::leak(element_container.unwrap()).0.as_ref()
+}
+
+/// New element container.
+///
+/// Returns a null pointer if the element is not supported.
+#[no_mangle]
+pub extern "C" fn element_container_new(
+    family: ElementFamily,
+    cell_type: ReferenceCellType,
+    degree: size_t,
+    continuity: Continuity,
+) -> Option<Box<ElementContainer>> {
+    create_element(family, cell_type, degree, continuity)
+        .ok()
+        .map(|e| ElementContainer(e).to_box())
+}
+
+/// Destroy an element container.
+#[no_mangle]
+pub extern "C" fn element_container_destroy(_: Option<Box<ElementContainer>>) {}
+
+/// Get the number of basis functions.
+#[no_mangle]
+pub extern "C" fn element_container_get_dim(
+    element_container: Option<Box<ElementContainer>>,
+) -> size_t {
+    get_element(element_container).dim()
+}
+
+/// Get the value size.
+#[no_mangle]
+pub extern "C" fn element_container_get_value_size(
+    element_container: Option<Box<ElementContainer>>,
+) -> size_t {
+    get_element(element_container).value_size()
+}
+
+/// Get the degree.
+#[no_mangle]
+pub extern "C" fn element_container_get_degree(
+    element_container: Option<Box<ElementContainer>>,
+) -> size_t {
+    get_element(element_container).degree()
+}
+
+/// Get the highest degree of a polynomial in the element's polynomial set.
+#[no_mangle]
+pub extern "C" fn element_container_get_highest_degree(
+    element_container: Option<Box<ElementContainer>>,
+) -> size_t {
+    get_element(element_container).highest_degree()
+}
+
+#[no_mangle]
+pub extern "C" fn element_container_get_family(
+    element_container: Option<Box<ElementContainer>>,
+) -> ElementFamily {
+    get_element(element_container).family()
+}
+
+#[no_mangle]
+pub extern "C" fn element_container_get_cell_type(
+    element_container: Option<Box<ElementContainer>>,
+) -> ReferenceCellType {
+    get_element(element_container).cell_type()
+}
+
+#[no_mangle]
+pub extern "C" fn element_container_get_map_type(
+    element_container: Option<Box<ElementContainer>>,
+) -> MapType {
+    get_element(element_container).map_type()
+}
+
+#[no_mangle]
+pub extern "C" fn element_container_get_discontinuous(
+    element_container: Option<Box<ElementContainer>>,
+) -> bool {
+    get_element(element_container).discontinuous()
+}
+
+#[no_mangle]
+pub extern "C" fn element_container_get_entity_dofs(
+    entity_dim: usize,
+    entity_number: usize,
+    element_container: Option<Box<ElementContainer>>,
+) -> Box<RustyDataContainer> {
+    assert!(entity_dim < 4);
+    let element = get_element(element_container);
+    RustyDataContainer::from_vec(element.entity_dofs(entity_dim, entity_number)).to_box()
+}
+
+#[no_mangle]
+pub extern "C" fn element_container_get_entity_closure_dofs(
+    entity_dim: usize,
+    entity_number: usize,
+    element_container: Option<Box<ElementContainer>>,
+) -> Box<RustyDataContainer> {
+    assert!(entity_dim < 4);
+    let element = get_element(element_container);
+    RustyDataContainer::from_vec(element.entity_closure_dofs(entity_dim, entity_number)).to_box()
+}
+
+/// Get the number of derivatives that are tabulated when tabulating up to order `nderivs`.
+#[no_mangle]
+pub extern "C" fn element_container_get_derivative_count(
+    nderivs: usize,
+    element_container: Option<Box<ElementContainer>>,
+) -> usize {
+    derivative_count(get_element(element_container).cell_type(), nderivs)
+}
+
+/// Tabulate the basis functions and their derivatives at a set of points.
+///
+/// The points must be a container of f64 values, with the first tdim values giving the first
+/// point, the next tdim the second point, and so on. The returned container has shape
+/// (derivative count, number of points, dim, value size).
+#[no_mangle]
+pub extern "C" fn element_container_tabulate(
+    points: Option<Box<RustyDataContainer>>,
+    nderivs: usize,
+    element_container: Option<Box<ElementContainer>>,
+) -> Box<RustyDataContainer> {
+    let element = get_element(element_container);
+    let points = unsafe { RustyDataContainer::as_slice::<f64>(points) };
+    let tdim = create_cell(element.cell_type()).dim();
+    let npoints = points.len() / tdim;
+    let mut data = TabulatedData::new(element, nderivs, npoints);
+    element.tabulate(points, nderivs, &mut data);
+    RustyDataContainer::from_vec(data.into_vec()).to_box()
+}
+
+/// Get the points at which a function is evaluated to compute its DOF values.
+///
+/// The first tdim values of the returned container give the first point, the next tdim the
+/// second point, and so on.
+#[no_mangle]
+pub extern "C" fn element_container_get_interpolation_points(
+    element_container: Option<Box<ElementContainer>>,
+) -> Box<RustyDataContainer> {
+    let element = get_element(element_container);
+    RustyDataContainer::from_vec(element.interpolation_points()).to_box()
+}
+
+/// Get the interpolation matrix, which maps the values of a function at the interpolation points
+/// to its DOF values.
+///
+/// The returned container has shape (dim, value size, number of points).
+#[no_mangle]
+pub extern "C" fn element_container_get_interpolation_matrix(
+    element_container: Option<Box<ElementContainer>>,
+) -> Box<RustyDataContainer> {
+    let element = get_element(element_container);
+    RustyDataContainer::from_vec(element.interpolation_matrix()).to_box()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::c_api::*;
+    use approx::*;
+
+    #[test]
+    fn test_element_container() {
+        let element = Box::into_raw(
+            element_container_new(
+                ElementFamily::Lagrange,
+                ReferenceCellType::Triangle,
+                1,
+                Continuity::Continuous,
+            )
+            .unwrap(),
+        );
+        let get = || Some(unsafe { Box::from_raw(element) });
+        assert_eq!(element_container_get_dim(get()), 3);
+        assert_eq!(element_container_get_value_size(get()), 1);
+        assert_eq!(element_container_get_degree(get()), 1);
+        assert_eq!(element_container_get_map_type(get()), MapType::Identity);
+        assert_eq!(element_container_get_derivative_count(1, get()), 3);
+        let dofs = element_container_get_entity_dofs(0, 2, get());
+        assert_eq!(
+            unsafe { RustyDataContainer::as_slice::<usize>(Some(dofs)) },
+            [2]
+        );
+        let dofs = element_container_get_entity_closure_dofs(1, 0, get());
+        assert_eq!(
+            unsafe { RustyDataContainer::as_slice::<usize>(Some(dofs)) },
+            [1, 2]
+        );
+        let points = element_container_get_interpolation_points(get());
+        assert_eq!(
+            unsafe { RustyDataContainer::as_slice::<f64>(Some(points)) },
+            [0.0, 0.0, 1.0, 0.0, 0.0, 1.0]
+        );
+        let matrix = element_container_get_interpolation_matrix(get());
+        assert_eq!(
+            unsafe { RustyDataContainer::as_slice::<f64>(Some(matrix)) }.len(),
+            9
+        );
+
+        let points = [0.2, 0.3, 1.0, 0.0];
+        let points = RustyDataContainer::from_slice(&points).to_box();
+        let data = element_container_tabulate(Some(points), 1, get());
+        let data = unsafe { RustyDataContainer::as_slice::<f64>(Some(data)) };
+        assert_eq!(data.len(), 18);
+        let expected = [0.5, 0.2, 0.3, 0.0, 1.0, 0.0];
+        for (i, e) in expected.iter().enumerate() {
+            assert_relative_eq!(data[i], e, epsilon = 1e-12);
+        }
+        assert_relative_eq!(data[6], -1.0, epsilon = 1e-12);
+        element_container_destroy(get());
+    }
+
+    #[test]
+    fn test_unsupported_element_container() {
+        assert!(element_container_new(
+            ElementFamily::RaviartThomas,
+            ReferenceCellType::Prism,
+            1,
+            Continuity::Continuous,
+        )
+        .is_none());
+    }
+}
@@ -1,6 +1,7 @@
 //! Cell definitions
 
 use crate::element::*;
+use crate::linalg::jacobian_det_and_inverse;
 pub mod cells_1d;
 pub use cells_1d::*;
 pub mod cells_2d;
@@ -44,6 +45,7 @@ pub trait ReferenceCell {
     fn faces_nvertices(&self) -> &[usize];
 
     /// The number of entities of dimension `dim`
+    #[allow(clippy::result_unit_err)]
     fn entity_count(&self, dim: usize) -> Result<usize, ()> {
         match dim {
             0 => Ok(self.vertex_count()),
@@ -70,6 +72,7 @@ pub trait ReferenceCell {
     ///
     /// This function returns a list of entity numbers of entities of dimension `connected_dim` that are attached to the entity numbered `entity_dim` of number entity_number.
     /// For example connectivity(1, 0, 2) will return a list of faces (2D entities) that are connected to edge (1D entity) 0.
+    #[allow(clippy::result_unit_err)]
     fn connectivity(
         &self,
         entity_dim: usize,
@@ -84,7 +87,71 @@ pub trait ReferenceCell {
     fn label(&self) -> &'static str;
 }
 
-pub struct PhysicalCell<'a, F: FiniteElement, C: ReferenceCell> {
+/// Create the reference cell of the given type
+pub fn create_cell(cell_type: ReferenceCellType) -> Box<dyn ReferenceCell> {
+    match cell_type {
+        ReferenceCellType::Interval => Box::new(Interval {}),
+        ReferenceCellType::Triangle => Box::new(Triangle {}),
+        ReferenceCellType::Quadrilateral => Box::new(Quadrilateral {}),
+        ReferenceCellType::Tetrahedron => Box::new(Tetrahedron {}),
+        ReferenceCellType::Hexahedron => Box::new(Hexahedron {}),
+        ReferenceCellType::Prism => Box::new(Prism {}),
+        ReferenceCellType::Pyramid => Box::new(Pyramid {}),
+    }
+}
+
+/// Compute the orientation bitfield of a cell from the global indices of its vertices
+///
+/// Each sub-entity is oriented so that its vertex with the lowest global index comes first. For
+/// an edge, the bit is set if the global index of its first vertex is greater than that of its
+/// second vertex. For a face with n vertices, the number of rotations is (n - p) % n, where p is
+/// the position of the lowest global vertex when going around the face, and the face is
+/// reflected if the next vertex after this one has a higher global index than the previous
+/// vertex. The vertices of a quadrilateral face are numbered in tensor product order, so are
+/// visited in the order 0, 1, 3, 2.
+///
+/// The number of rotations matches the base rotation of a face used by the DOF transformations,
+/// which maps vertex 1 of the face to vertex 0. This differs from DOLFINx, where the number of
+/// rotations is p, so a bitfield computed by DOLFINx cannot be used with the DOF transformations
+/// of this library.
+///
+/// The format of the bitfield is described in [FiniteElement::t_apply].
+pub fn compute_cell_info<C: ReferenceCell + ?Sized>(cell: &C, vertices: &[usize]) -> u32 {
+    assert_eq!(vertices.len(), cell.vertex_count());
+    let tdim = cell.dim();
+    let mut cell_info = 0;
+    let face_start = if tdim == 3 { 3 * cell.face_count() } else { 0 };
+    if tdim > 1 {
+        for (e, edge) in cell.edges().chunks(2).enumerate() {
+            if vertices[edge[0]] > vertices[edge[1]] {
+                cell_info |= 1 << (face_start + e);
+            }
+        }
+    }
+    if tdim == 3 {
+        let mut start = 0;
+        for (f, nvertices) in cell.faces_nvertices().iter().enumerate() {
+            let face = &cell.faces()[start..start + nvertices];
+            start += nvertices;
+            let cycle = match nvertices {
+                3 => vec![face[0], face[1], face[2]],
+                4 => vec![face[0], face[1], face[3], face[2]],
+                _ => panic!("Unsupported face"),
+            };
+            let n = cycle.len();
+            let lowest = (0..n).min_by_key(|i| vertices[cycle[*i]]).unwrap();
+            let post = vertices[cycle[(lowest + 1) % n]];
+            let pre = vertices[cycle[(lowest + n - 1) % n]];
+            cell_info |= (((n - lowest) % n) as u32) << (3 * f + 1);
+            if post > pre {
+                cell_info |= 1 << (3 * f);
+            }
+        }
+    }
+    cell_info
+}
+
+pub struct PhysicalCell<'a, F: FiniteElement + ?Sized, C: ReferenceCell> {
     reference_cell: &'a C,
     vertices: &'a [f64],
     coordinate_element: &'a F,
@@ -93,7 +160,7 @@ pub struct PhysicalCell<'a, F: FiniteElement, C: ReferenceCell> {
     npts: usize,
 }
 
-impl<'a, F: FiniteElement, C: ReferenceCell> PhysicalCell<'a, F, C> {
+impl<'a, F: FiniteElement + ?Sized, C: ReferenceCell> PhysicalCell<'a, F, C> {
     pub fn new(
         reference_cell: &'a C,
         vertices: &'a [f64],
@@ -112,6 +179,9 @@ impl<'a, F: FiniteElement, C: ReferenceCell> PhysicalCell<'a, F, C> {
         }
     }
 
+    pub fn reference_cell(&self) -> &'a C {
+        self.reference_cell
+    }
     pub fn tdim(&self) -> usize {
         self.tdim
     }
@@ -127,11 +197,164 @@ impl<'a, F: FiniteElement, C: ReferenceCell> PhysicalCell<'a, F, C> {
     pub fn vertices(&self) -> &'a [f64] {
         self.vertices
     }
+
+    /// Map points on the reference cell to the physical cell
+    ///
+    /// The reference points have tdim components and the physical points have gdim components.
+    pub fn push_forward_points(&self, reference_points: &[f64]) -> Vec<f64> {
+        let npts = reference_points.len() / self.tdim;
+        let mut table = TabulatedData::new(self.coordinate_element, 0, npts);
+        self.coordinate_element
+            .tabulate(reference_points, 0, &mut table);
+
+        let mut physical_points = vec![0.0; npts * self.gdim];
+        for (p, x) in physical_points.chunks_mut(self.gdim).enumerate() {
+            for gp in 0..self.npts {
+                for (a, xa) in x.iter_mut().enumerate() {
+                    *xa += table.get(0, p, gp, 0) * self.vertices[gp * self.gdim + a];
+                }
+            }
+        }
+        physical_points
+    }
+
+    /// Map points on the physical cell to the reference cell
+    ///
+    /// This uses Newton iteration with a tolerance of 1e-12 and at most 20 iterations: see
+    /// [PhysicalCell::pull_back_points_with_tolerance].
+    #[allow(clippy::result_unit_err)]
+    pub fn pull_back_points(&self, physical_points: &[f64]) -> Result<Vec<f64>, ()> {
+        self.pull_back_points_with_tolerance(physical_points, 1e-12, 20)
+    }
+
+    /// Map points on the physical cell to the reference cell using Newton iteration
+    ///
+    /// Starting from the midpoint of the reference cell, each iteration updates the reference
+    /// point X by K (x - F(X)), where F is the map from the reference cell and K is the
+    /// (pseudo-)inverse of its Jacobian. For affine cells, this converges in one iteration. The
+    /// iteration stops when the size of the update is less than `tolerance`; if this does not
+    /// happen within `max_iterations` iterations for any point, an error is returned.
+    ///
+    /// Points outside the physical cell are mapped to points outside the reference cell. If
+    /// tdim is less than gdim, the result is the reference point whose image is closest to
+    /// each physical point.
+    #[allow(clippy::result_unit_err)]
+    pub fn pull_back_points_with_tolerance(
+        &self,
+        physical_points: &[f64],
+        tolerance: f64,
+        max_iterations: usize,
+    ) -> Result<Vec<f64>, ()> {
+        let tdim = self.tdim;
+        let gdim = self.gdim;
+        let nvertices = self.reference_cell.vertex_count();
+        let midpoint = (0..tdim)
+            .map(|i| {
+                (0..nvertices)
+                    .map(|v| self.reference_cell.vertices()[v * tdim + i])
+                    .sum::<f64>()
+                    / nvertices as f64
+            })
+            .collect::<Vec<_>>();
+
+        let mut reference_points = vec![];
+        for x in physical_points.chunks(gdim) {
+            let mut point = midpoint.clone();
+            let mut converged = false;
+            for _ in 0..max_iterations {
+                let residual = self
+                    .push_forward_points(&point)
+                    .iter()
+                    .zip(x)
+                    .map(|(fx, xi)| xi - fx)
+                    .collect::<Vec<_>>();
+                let k = self.inverse_jacobians(&point);
+                let mut update_norm = 0.0;
+                for (a, pa) in point.iter_mut().enumerate() {
+                    let update = (0..gdim)
+                        .map(|b| k[a * gdim + b] * residual[b])
+                        .sum::<f64>();
+                    *pa += update;
+                    update_norm += update * update;
+                }
+                if update_norm.sqrt() < tolerance {
+                    converged = true;
+                    break;
+                }
+            }
+            if !converged {
+                return Err(());
+            }
+            reference_points.extend(point);
+        }
+        Ok(reference_points)
+    }
+
+    /// The Jacobian of the map from the reference cell at each point
+    ///
+    /// The Jacobians are returned as gdim by tdim row-major matrices.
+    pub fn jacobians(&self, points: &[f64]) -> Vec<f64> {
+        let npts = points.len() / self.tdim;
+
+        let mut derivs = TabulatedData::new(self.coordinate_element, 1, npts);
+        self.coordinate_element.tabulate(points, 1, &mut derivs);
+
+        let mut jacobians = vec![0.0; npts * self.gdim * self.tdim];
+        for (p, j) in jacobians.chunks_mut(self.gdim * self.tdim).enumerate() {
+            for gp in 0..self.npts {
+                for a in 0..self.gdim {
+                    for b in 0..self.tdim {
+                        j[a * self.tdim + b] +=
+                            derivs.get(1 + b, p, gp, 0) * self.vertices[gp * self.gdim + a];
+                    }
+                }
+            }
+        }
+        jacobians
+    }
+
+    /// The determinant of the Jacobian at each point
+    ///
+    /// If tdim is less than gdim, this is sqrt(det(J^T J)).
+    pub fn jacobian_determinants(&self, points: &[f64]) -> Vec<f64> {
+        self.jacobians_determinants_and_inverses(points).1
+    }
+
+    /// The inverse of the Jacobian at each point
+    ///
+    /// The inverses are returned as tdim by gdim row-major matrices. If tdim is less than gdim,
+    /// this is the pseudo-inverse (J^T J)^{-1} J^T.
+    pub fn inverse_jacobians(&self, points: &[f64]) -> Vec<f64> {
+        self.jacobians_determinants_and_inverses(points).2
+    }
+
+    /// The Jacobian, its determinant and its inverse at each point
+    pub(crate) fn jacobians_determinants_and_inverses(
+        &self,
+        points: &[f64],
+    ) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        let tdim = self.tdim;
+        let gdim = self.gdim;
+        let jacobians = self.jacobians(points);
+        let npts = jacobians.len() / (gdim * tdim);
+        let mut dets = vec![0.0; npts];
+        let mut inverses = vec![0.0; npts * tdim * gdim];
+        for ((j, det), k) in jacobians
+            .chunks(gdim * tdim)
+            .zip(dets.iter_mut())
+            .zip(inverses.chunks_mut(tdim * gdim))
+        {
+            *det = jacobian_det_and_inverse(j, gdim, tdim, k);
+        }
+        (jacobians, dets, inverses)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::cell::*;
+    use crate::map::push_forward;
+    use approx::*;
     use paste::paste;
 
     macro_rules! test_cell {
@@ -213,4 +436,401 @@ mod test {
         Prism,
         Pyramid
     );
+
+    #[test]
+    fn test_jacobians() {
+        let coord_e = LagrangeElementTriangleDegree1 {};
+        let ref_cell = Triangle {};
+        let vertices = vec![0.0, 1.0, 1.0, 0.0, 2.0, 1.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        let pts = vec![0.3, 0.3, 0.1, 0.7];
+
+        let j = geometry.jacobians(&pts);
+        assert_eq!(j.len(), 8);
+        for (a, b) in j.iter().zip([1.0, 2.0, -1.0, 0.0, 1.0, 2.0, -1.0, 0.0]) {
+            assert_relative_eq!(*a, b, epsilon = 1e-14);
+        }
+        for d in geometry.jacobian_determinants(&pts) {
+            assert_relative_eq!(d, 2.0, epsilon = 1e-14);
+        }
+        let k = geometry.inverse_jacobians(&pts);
+        for (a, b) in k.iter().zip([0.0, -1.0, 0.5, 0.5, 0.0, -1.0, 0.5, 0.5]) {
+            assert_relative_eq!(*a, b, epsilon = 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_push_forward_and_pull_back_points() {
+        let coord_e = LagrangeElementTriangleDegree1 {};
+        let ref_cell = Triangle {};
+        let vertices = vec![0.0, 1.0, 1.0, 0.0, 2.0, 1.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        let pts = vec![0.3, 0.3, 0.1, 0.7, 1.0, 0.0];
+
+        let x = geometry.push_forward_points(&pts);
+        for (a, b) in x.iter().zip([0.9, 0.7, 1.5, 0.9, 1.0, 0.0]) {
+            assert_relative_eq!(*a, b, epsilon = 1e-14);
+        }
+        let pts2 = geometry.pull_back_points(&x).unwrap();
+        for (a, b) in pts2.iter().zip(&pts) {
+            assert_relative_eq!(a, b, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_pull_back_points_non_affine() {
+        // A bilinear quadrilateral
+        let coord_e = LagrangeElement::new(ReferenceCellType::Quadrilateral, 1);
+        let ref_cell = Quadrilateral {};
+        let vertices = vec![0.0, 0.0, 2.0, 0.0, 0.0, 1.0, 3.0, 2.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        let pts = vec![0.3, 0.3, 0.9, 0.1, 0.0, 1.0, 0.5, 0.75];
+        let x = geometry.push_forward_points(&pts);
+        let pts2 = geometry.pull_back_points(&x).unwrap();
+        for (a, b) in pts2.iter().zip(&pts) {
+            assert_relative_eq!(a, b, epsilon = 1e-12);
+        }
+        assert!(geometry
+            .pull_back_points_with_tolerance(&x, 1e-12, 1)
+            .is_err());
+
+        // A P2 triangle with a curved edge
+        let coord_e = LagrangeElement::new(ReferenceCellType::Triangle, 2);
+        let ref_cell = Triangle {};
+        let vertices = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.6, 0.6, 0.0, 0.5, 0.5, 0.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        let pts = vec![0.2, 0.3, 0.5, 0.5, 0.1, 0.0];
+        let x = geometry.push_forward_points(&pts);
+        let pts2 = geometry.pull_back_points(&x).unwrap();
+        for (a, b) in pts2.iter().zip(&pts) {
+            assert_relative_eq!(a, b, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_jacobians_manifold() {
+        // A bilinear quadrilateral in 3D
+        let coord_e = LagrangeElement::new(ReferenceCellType::Quadrilateral, 1);
+        let ref_cell = Quadrilateral {};
+        let vertices = vec![0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 2.0, 0.0, 1.5, 2.0, 0.5];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 3);
+        let pts = vec![0.5, 0.25];
+
+        let j = geometry.jacobians(&pts);
+        assert_eq!(j.len(), 6);
+        let k = geometry.inverse_jacobians(&pts);
+        assert_eq!(k.len(), 6);
+        // K is a left inverse of J
+        for a in 0..2 {
+            for b in 0..2 {
+                let kj = (0..3).map(|c| k[a * 3 + c] * j[c * 2 + b]).sum::<f64>();
+                assert_relative_eq!(kj, if a == b { 1.0 } else { 0.0 }, epsilon = 1e-14);
+            }
+        }
+        // The determinant is the area scaling, |J_0 x J_1|
+        let cross = [
+            j[2] * j[5] - j[4] * j[3],
+            j[4] * j[1] - j[0] * j[5],
+            j[0] * j[3] - j[2] * j[1],
+        ];
+        assert_relative_eq!(
+            geometry.jacobian_determinants(&pts)[0],
+            cross.iter().map(|x| x * x).sum::<f64>().sqrt(),
+            epsilon = 1e-14
+        );
+    }
+
+    #[test]
+    fn test_compute_cell_info() {
+        let cell = Triangle {};
+        assert_eq!(compute_cell_info(&cell, &[0, 1, 2]), 0);
+        assert_eq!(compute_cell_info(&cell, &[2, 1, 0]), 0b111);
+        assert_eq!(compute_cell_info(&cell, &[5, 1, 4]), 0b110);
+
+        let cell = Tetrahedron {};
+        assert_eq!(compute_cell_info(&cell, &[0, 1, 2, 3]), 0);
+        // Face 0 has vertices (1, 2, 3), with global indices (3, 1, 2): the lowest is in position
+        // 1 and the next vertex has a lower index than the previous one
+        let info = compute_cell_info(&cell, &[0, 3, 1, 2]);
+        assert_eq!(info & 0b111, 0b100);
+        let info = compute_cell_info(&cell, &[0, 3, 2, 1]);
+        assert_eq!(info & 0b111, 0b011);
+
+        let cell = Hexahedron {};
+        assert_eq!(compute_cell_info(&cell, &[0, 1, 2, 3, 4, 5, 6, 7]), 0);
+        // Face 0 has vertices (0, 1, 2, 3), visited in the order 0, 1, 3, 2
+        let info = compute_cell_info(&cell, &[3, 2, 1, 0, 4, 5, 6, 7]);
+        assert_eq!(info & 0b111, 0b100);
+    }
+
+    /// All permutations of a list
+    fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+        if items.len() <= 1 {
+            return vec![items.to_vec()];
+        }
+        let mut result = vec![];
+        for i in 0..items.len() {
+            let mut rest = items.to_vec();
+            let first = rest.remove(i);
+            for mut p in permutations(&rest) {
+                p.insert(0, first);
+                result.push(p);
+            }
+        }
+        result
+    }
+
+    /// Check that after applying the DOF transformations, the DOFs associated with the
+    /// sub-entities of an edge or face shared by two cells agree on the shared sub-entity
+    ///
+    /// Each cell is given by the global indices of its vertices, in local order, and the shared
+    /// sub-entity by the sorted global indices of its vertices. The matching DOFs of each cell in
+    /// `cells0` and each cell in `cells1` are compared. For vector-valued elements, the tangential
+    /// components are compared.
+    fn check_shared_entity<C: ReferenceCell>(
+        ref_cell: &C,
+        e: &CiarletElement,
+        coords: &[[f64; 3]],
+        cells0: &[Vec<usize>],
+        cells1: &[Vec<usize>],
+        shared: &[usize],
+        physical_points: &[f64],
+    ) {
+        let npts = physical_points.len() / 3;
+        let coord_e = create_lagrange_element(e.cell_type(), 1, false);
+        let tangents = shared[1..]
+            .iter()
+            .map(|v| [0, 1, 2].map(|j| coords[*v][j] - coords[shared[0]][j]))
+            .collect::<Vec<_>>();
+
+        // The values of the transformed basis functions at the points
+        let values_on_cell = |global_vertices: &[usize]| {
+            let vertices = global_vertices
+                .iter()
+                .flat_map(|v| coords[*v])
+                .collect::<Vec<_>>();
+            let geometry = PhysicalCell::new(ref_cell, &vertices, &coord_e, 3);
+            let points = geometry.pull_back_points(physical_points).unwrap();
+            let mut table = TabulatedData::new(e, 0, npts);
+            e.tabulate(&points, 0, &mut table);
+            let mut physical = TabulatedData::new(e, 0, npts);
+            push_forward(e, &table, &points, &geometry, &mut physical);
+
+            let ncomponents = if e.value_size() == 1 {
+                1
+            } else {
+                tangents.len()
+            };
+            let n = npts * ncomponents;
+            let mut data = vec![0.0; e.dim() * n];
+            for i in 0..e.dim() {
+                for p in 0..npts {
+                    for c in 0..ncomponents {
+                        data[i * n + p * ncomponents + c] = if e.value_size() == 1 {
+                            *physical.get(0, p, i, 0)
+                        } else {
+                            (0..3)
+                                .map(|v| physical.get(0, p, i, v) * tangents[c][v])
+                                .sum()
+                        };
+                    }
+                }
+            }
+            // T acts on DOF values, so the values of the basis functions are transformed by the
+            // inverse transpose of T
+            e.t_inv_transpose_apply(&mut data, n, compute_cell_info(ref_cell, global_vertices));
+            (data, n)
+        };
+        // The sorted global vertices of each sub-entity of a cell
+        let entity_vertices = |global_vertices: &[usize], dim: usize| {
+            (0..ref_cell.entity_count(dim).unwrap())
+                .map(|i| {
+                    let mut v = ref_cell
+                        .connectivity(dim, i, 0)
+                        .unwrap()
+                        .iter()
+                        .map(|v| global_vertices[*v])
+                        .collect::<Vec<_>>();
+                    v.sort();
+                    v
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for cell0 in cells0 {
+            let (data0, n) = values_on_cell(cell0);
+            for cell1 in cells1 {
+                let (data1, _) = values_on_cell(cell1);
+                for dim in 0..3 {
+                    let entities1 = entity_vertices(cell1, dim);
+                    for (i, entity) in entity_vertices(cell0, dim).iter().enumerate() {
+                        if !entity.iter().all(|v| shared.contains(v)) {
+                            continue;
+                        }
+                        let j = entities1.iter().position(|v| v == entity).unwrap();
+                        let dofs0 = e.entity_dofs(dim, i);
+                        let dofs1 = e.entity_dofs(dim, j);
+                        for (d0, d1) in dofs0.iter().zip(&dofs1) {
+                            for k in 0..n {
+                                assert_relative_eq!(
+                                    data0[d0 * n + k],
+                                    data1[d1 * n + k],
+                                    epsilon = 1e-10
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shared_face_continuity_tetrahedron() {
+        let coords = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0],
+        ];
+        // Points on the face x + y + z = 1
+        let points = [0.2, 0.3, 0.5, 0.6, 0.1, 0.3, 0.1, 0.15, 0.75];
+        let cells0 = [vec![0, 1, 2, 3], vec![2, 3, 0, 1], vec![3, 1, 0, 2]];
+        let cells1 = permutations(&[1, 2, 3, 4]);
+        for e in [
+            create_lagrange_element(ReferenceCellType::Tetrahedron, 5, false),
+            create_nedelec_element(ReferenceCellType::Tetrahedron, 3, false),
+            create_nedelec_second_kind_element(ReferenceCellType::Tetrahedron, 3, false),
+        ] {
+            check_shared_entity(
+                &Tetrahedron {},
+                &e,
+                &coords,
+                &cells0,
+                &cells1,
+                &[1, 2, 3],
+                &points,
+            );
+        }
+    }
+
+    #[test]
+    fn test_shared_face_continuity_hexahedron() {
+        // Two unit cubes sharing the face x = 1, with the vertices of the grid numbered in a
+        // scrambled order
+        let numbering = [5, 11, 0, 7, 2, 9, 4, 1, 10, 3, 8, 6];
+        let mut coords = [[0.0; 3]; 12];
+        for (i, n) in numbering.iter().enumerate() {
+            coords[*n] = [(i % 3) as f64, (i / 3 % 2) as f64, (i / 6) as f64];
+        }
+        // The numberings of the vertices of a cube given by each of its 48 symmetries
+        let cube = |offset: usize| {
+            let mut cells = vec![];
+            for axes in permutations(&[0, 1, 2]) {
+                for flips in 0..8 {
+                    cells.push(
+                        (0..8)
+                            .map(|v| {
+                                let c = [0, 1, 2].map(|a| (v >> axes[a]) & 1 ^ (flips >> a) & 1);
+                                numbering[c[0] + offset + 3 * c[1] + 6 * c[2]]
+                            })
+                            .collect::<Vec<_>>(),
+                    );
+                }
+            }
+            cells
+        };
+        let cells0 = cube(0).into_iter().step_by(13).collect::<Vec<_>>();
+        let cells1 = cube(1);
+        let mut face = [1, 4, 7, 10].map(|i| numbering[i]);
+        face.sort();
+        let points = [1.0, 0.2, 0.3, 1.0, 0.7, 0.4, 1.0, 0.35, 0.85];
+        for e in [
+            create_lagrange_element(ReferenceCellType::Hexahedron, 3, false),
+            create_nce_element(ReferenceCellType::Hexahedron, 2, false),
+        ] {
+            check_shared_entity(
+                &Hexahedron {},
+                &e,
+                &coords,
+                &cells0,
+                &cells1,
+                &face,
+                &points,
+            );
+        }
+    }
+
+    #[test]
+    fn test_shared_edge_continuity_tetrahedron() {
+        // Two tetrahedra that only share the edge between vertices 1 and 2
+        let coords = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 1.0, 0.0],
+            [1.0, 1.0, -1.0],
+        ];
+        let points = [0.3, 0.7, 0.0, 0.8, 0.2, 0.0];
+        let cells0 = [vec![0, 1, 2, 3], vec![3, 2, 1, 0]];
+        let cells1 = permutations(&[1, 2, 4, 5]);
+        for e in [
+            create_lagrange_element(ReferenceCellType::Tetrahedron, 4, false),
+            create_nedelec_element(ReferenceCellType::Tetrahedron, 3, false),
+        ] {
+            check_shared_entity(
+                &Tetrahedron {},
+                &e,
+                &coords,
+                &cells0,
+                &cells1,
+                &[1, 2],
+                &points,
+            );
+        }
+    }
+
+    #[test]
+    fn test_shared_face_continuity_prism() {
+        // Two prisms that share the quadrilateral face y = 0
+        let coords = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0],
+            [0.0, -1.0, 0.0],
+            [0.0, -1.0, 1.0],
+        ];
+        // The numberings of the vertices of a prism given by each of its 12 symmetries, where
+        // the prism has the vertices `bottom` at z = 0 and `top` at z = 1
+        let prism = |bottom: [usize; 3], top: [usize; 3]| {
+            let mut cells = vec![];
+            for p in permutations(&[0, 1, 2]) {
+                for flip in [false, true] {
+                    cells.push(
+                        (0..6)
+                            .map(|v| if (v >= 3) != flip { top } else { bottom }[p[v % 3]])
+                            .collect::<Vec<_>>(),
+                    );
+                }
+            }
+            cells
+        };
+        let cells0 = prism([0, 1, 2], [3, 4, 5]);
+        let cells1 = prism([0, 1, 6], [3, 4, 7]);
+        let points = [0.2, 0.0, 0.3, 0.7, 0.0, 0.4, 0.35, 0.0, 0.85];
+        check_shared_entity(
+            &Prism {},
+            &create_lagrange_element(ReferenceCellType::Prism, 3, false),
+            &coords,
+            &cells0,
+            &cells1,
+            &[0, 1, 3, 4],
+            &points,
+        );
+    }
 }
@@ -2,23 +2,51 @@
 
 use crate::cell::*;
 use crate::map::*;
+use crate::polyset::derivative_count;
+use transformations::{apply_dof_permutations, apply_dof_transformations, matrix_to_permutation};
+pub mod brezzi_douglas_marini;
+pub use brezzi_douglas_marini::*;
+pub mod ciarlet;
+pub use ciarlet::*;
+pub mod hhj;
+pub use hhj::*;
+pub mod interpolation;
+pub use interpolation::*;
 pub mod lagrange;
 pub use lagrange::*;
+mod moments;
+pub mod nce_rtc;
+pub use nce_rtc::*;
+pub mod nedelec;
+pub use nedelec::*;
 pub mod raviart_thomas;
 pub use raviart_thomas::*;
+pub mod regge;
+pub use regge::*;
+mod transformations;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 #[repr(u8)]
 pub enum ElementFamily {
     Lagrange = 0,
     RaviartThomas = 1,
+    NedelecFirstKind = 2,
+    BrezziDouglasMarini = 3,
+    NedelecSecondKind = 4,
+    Regge = 5,
+    HellanHerrmannJohnson = 6,
 }
 
-/// A finite element
-pub trait FiniteElement: Sized {
-    const VALUE_SIZE: usize;
-    const MAP_TYPE: MapType;
+/// The continuity of a finite element
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(u8)]
+pub enum Continuity {
+    Continuous = 0,
+    Discontinuous = 1,
+}
 
+/// A finite element
+pub trait FiniteElement {
     fn cell_type(&self) -> ReferenceCellType;
     fn degree(&self) -> usize;
     fn highest_degree(&self) -> usize;
@@ -26,51 +54,267 @@ pub trait FiniteElement: Sized {
     fn dim(&self) -> usize;
     fn discontinuous(&self) -> bool;
 
-    fn value_size(&self) -> usize {
-        Self::VALUE_SIZE
-    }
+    fn value_size(&self) -> usize;
 
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>);
+    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData);
 
     fn entity_dofs(&self, entity_dim: usize, entity_number: usize) -> Vec<usize>;
 
-    fn map_type(&self) -> MapType {
-        Self::MAP_TYPE
+    /// The DOFs associated with the closure of a sub-entity
+    ///
+    /// These are the DOFs associated with the sub-entity and with each of its sub-entities,
+    /// ordered by the dimension then the number of the sub-entity they are associated with.
+    fn entity_closure_dofs(&self, entity_dim: usize, entity_number: usize) -> Vec<usize> {
+        let cell = create_cell(self.cell_type());
+        let mut dofs = vec![];
+        for d in 0..=entity_dim {
+            for e in cell.connectivity(entity_dim, entity_number, d).unwrap() {
+                dofs.extend(self.entity_dofs(d, e));
+            }
+        }
+        dofs
+    }
+
+    /// The number of DOFs associated with each sub-entity
+    ///
+    /// Entry `[d][e]` is the number of DOFs associated with sub-entity `e` of dimension `d`.
+    fn num_entity_dofs(&self) -> Vec<Vec<usize>> {
+        let cell = create_cell(self.cell_type());
+        (0..=cell.dim())
+            .map(|d| {
+                (0..cell.entity_count(d).unwrap())
+                    .map(|e| self.entity_dofs(d, e).len())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The number of DOFs associated with the closure of each sub-entity
+    ///
+    /// Entry `[d][e]` is the number of DOFs associated with the closure of sub-entity `e` of
+    /// dimension `d`.
+    fn num_entity_closure_dofs(&self) -> Vec<Vec<usize>> {
+        let cell = create_cell(self.cell_type());
+        (0..=cell.dim())
+            .map(|d| {
+                (0..cell.entity_count(d).unwrap())
+                    .map(|e| self.entity_closure_dofs(d, e).len())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The points at which a function is evaluated to compute its DOF values
+    ///
+    /// The first tdim components are the first point, the next tdim the second point, and so on.
+    fn interpolation_points(&self) -> Vec<f64>;
+
+    /// The matrix that maps the values of a function at the interpolation points to its DOF values
+    ///
+    /// The matrix is a dim by (value_size * npoints) row-major matrix, where npoints is the number
+    /// of interpolation points: the DOF values of a function f are M f, where entry
+    /// `v * npoints + p` of f is component `v` of f at point `p`. The function f is defined on
+    /// the reference cell: a function on a physical cell should be pulled back first.
+    fn interpolation_matrix(&self) -> Vec<f64>;
+
+    fn map_type(&self) -> MapType;
+
+    /// The matrix of a base transformation of the DOFs associated with a sub-entity
+    ///
+    /// For an edge, transformation 0 is the reversal of the edge. For a face, transformation 0 is
+    /// a rotation of the face and transformation 1 is a reflection of the face. The matrix is an
+    /// n by n row-major matrix, where n is the number of DOFs associated with the sub-entity.
+    ///
+    /// The matrix acts on DOF values: entry (i, j) is DOF functional i applied to basis function j
+    /// pulled back by the map of the sub-entity. The values of the basis functions themselves are
+    /// transformed by the inverse transpose of this matrix.
+    fn entity_transformation(
+        &self,
+        entity_dim: usize,
+        entity_number: usize,
+        transformation: usize,
+    ) -> Vec<f64>;
+
+    /// The base transformations of the element, as dim by dim row-major matrices
+    ///
+    /// If tdim is 2 or 3, there is one base transformation for each edge (reversal). If tdim is 3,
+    /// these are followed by two for each face (rotation then reflection). As for
+    /// [FiniteElement::entity_transformation], these matrices act on DOF values.
+    fn base_transformations(&self) -> Vec<Vec<f64>> {
+        let cell = create_cell(self.cell_type());
+        let tdim = cell.dim();
+        let dim = self.dim();
+        let mut transformations = vec![];
+        for d in 1..usize::min(tdim, 3) {
+            for e in 0..cell.entity_count(d).unwrap() {
+                let dofs = self.entity_dofs(d, e);
+                let count = if d == 1 { 1 } else { 2 };
+                for t in 0..count {
+                    let mut matrix = vec![0.0; dim * dim];
+                    for i in 0..dim {
+                        matrix[i * dim + i] = 1.0;
+                    }
+                    if !dofs.is_empty() {
+                        let m = self.entity_transformation(d, e, t);
+                        for (i, di) in dofs.iter().enumerate() {
+                            for (j, dj) in dofs.iter().enumerate() {
+                                matrix[di * dim + dj] = m[i * dofs.len() + j];
+                            }
+                        }
+                    }
+                    transformations.push(matrix);
+                }
+            }
+        }
+        transformations
+    }
+
+    /// Whether the base transformations of the element are all permutations
+    fn dof_transformations_are_permutations(&self) -> bool {
+        let cell = create_cell(self.cell_type());
+        let tdim = cell.dim();
+        for d in 1..usize::min(tdim, 3) {
+            for e in 0..cell.entity_count(d).unwrap() {
+                let size = self.entity_dofs(d, e).len();
+                if size > 0 {
+                    for t in 0..if d == 1 { 1 } else { 2 } {
+                        let m = self.entity_transformation(d, e, t);
+                        if matrix_to_permutation(&m, size).is_none() {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+        true
     }
+
+    /// Permute the DOF numbers of a cell using the DOF transformation for the cell
+    ///
+    /// This can be used instead of [FiniteElement::t_apply] when
+    /// [FiniteElement::dof_transformations_are_permutations] is true: entry i of `dofs` is
+    /// replaced by entry j, where T maps DOF j to DOF i. See [FiniteElement::t_apply] for the
+    /// format of `cell_info`.
+    fn permute_dofs(&self, dofs: &mut [usize], cell_info: u32) {
+        apply_dof_permutations(self, dofs, cell_info, false);
+    }
+
+    /// Reverse the permutation applied by [FiniteElement::permute_dofs]
+    fn unpermute_dofs(&self, dofs: &mut [usize], cell_info: u32) {
+        apply_dof_permutations(self, dofs, cell_info, true);
+    }
+
+    /// Apply the DOF transformation T for a cell to data whose first index is the DOF number
+    ///
+    /// `data` has shape (dim, n). `cell_info` is a bitfield describing the orientation of the
+    /// sub-entities of the cell: if tdim is 3, bit 3f is set if face f is reflected and bits
+    /// 3f+1 and 3f+2 give the number of times it is rotated, and bit 3 * face_count + e is set
+    /// if edge e is reversed; if tdim is 2, bit e is set if edge e is reversed. For each face, T
+    /// applies the rotations before the reflection.
+    ///
+    /// T acts on DOF values. Data indexed by basis function, such as tabulated basis function
+    /// values, must be transformed with the inverse transpose of T using
+    /// [FiniteElement::t_inv_transpose_apply]: this gives basis functions that agree on the
+    /// sub-entities shared by neighbouring cells, and T maps the coefficients of a function in the
+    /// reference basis to its coefficients in the transformed basis.
+    fn t_apply(&self, data: &mut [f64], n: usize, cell_info: u32) {
+        apply_dof_transformations(self, data, n, cell_info, false, false);
+    }
+
+    /// Apply the inverse of the DOF transformation for a cell: see [FiniteElement::t_apply]
+    fn t_inv_apply(&self, data: &mut [f64], n: usize, cell_info: u32) {
+        apply_dof_transformations(self, data, n, cell_info, true, false);
+    }
+
+    /// Apply the transpose of the DOF transformation for a cell: see [FiniteElement::t_apply]
+    fn t_transpose_apply(&self, data: &mut [f64], n: usize, cell_info: u32) {
+        apply_dof_transformations(self, data, n, cell_info, false, true);
+    }
+
+    /// Apply the inverse transpose of the DOF transformation for a cell: see
+    /// [FiniteElement::t_apply]
+    fn t_inv_transpose_apply(&self, data: &mut [f64], n: usize, cell_info: u32) {
+        apply_dof_transformations(self, data, n, cell_info, true, true);
+    }
+}
+
+/// Create an element of the given family, cell type and degree
+///
+/// The Raviart-Thomas and Nédélec first kind families on quadrilaterals and hexahedra give the
+/// RTCF, NCF, RTCE and NCE elements. An error is returned if the family is not implemented on the
+/// cell or with the given degree.
+#[allow(clippy::result_unit_err)]
+pub fn create_element(
+    family: ElementFamily,
+    cell_type: ReferenceCellType,
+    degree: usize,
+    continuity: Continuity,
+) -> Result<Box<dyn FiniteElement>, ()> {
+    let discontinuous = continuity == Continuity::Discontinuous;
+    let simplex_cell =
+        cell_type == ReferenceCellType::Triangle || cell_type == ReferenceCellType::Tetrahedron;
+    let tensor_cell =
+        cell_type == ReferenceCellType::Quadrilateral || cell_type == ReferenceCellType::Hexahedron;
+    let supported = match family {
+        ElementFamily::Lagrange => true,
+        ElementFamily::RaviartThomas | ElementFamily::NedelecFirstKind => {
+            (simplex_cell || tensor_cell) && degree >= 1
+        }
+        ElementFamily::BrezziDouglasMarini | ElementFamily::NedelecSecondKind => {
+            simplex_cell && degree >= 1
+        }
+        ElementFamily::Regge | ElementFamily::HellanHerrmannJohnson => simplex_cell,
+    };
+    if !supported {
+        return Err(());
+    }
+    Ok(Box::new(match family {
+        ElementFamily::Lagrange => create_lagrange_element(cell_type, degree, discontinuous),
+        ElementFamily::RaviartThomas => {
+            if tensor_cell {
+                create_rtc_element(cell_type, degree, discontinuous)
+            } else {
+                create_raviart_thomas_element(cell_type, degree, discontinuous)
+            }
+        }
+        ElementFamily::NedelecFirstKind => {
+            if tensor_cell {
+                create_nce_element(cell_type, degree, discontinuous)
+            } else {
+                create_nedelec_element(cell_type, degree, discontinuous)
+            }
+        }
+        ElementFamily::BrezziDouglasMarini => {
+            create_brezzi_douglas_marini_element(cell_type, degree, discontinuous)
+        }
+        ElementFamily::NedelecSecondKind => {
+            create_nedelec_second_kind_element(cell_type, degree, discontinuous)
+        }
+        ElementFamily::Regge => create_regge_element(cell_type, degree, discontinuous),
+        ElementFamily::HellanHerrmannJohnson => {
+            create_hhj_element(cell_type, degree, discontinuous)
+        }
+    }))
 }
 
-pub struct TabulatedData<'a, F: FiniteElement> {
+/// Values of the basis functions of an element and their derivatives at a set of points
+pub struct TabulatedData {
     data: Vec<f64>,
-    element: &'a F,
     deriv_count: usize,
     point_count: usize,
     basis_count: usize,
     value_size: usize,
 }
 
-fn compute_derivative_count(nderivs: usize, cell_type: ReferenceCellType) -> Result<usize, ()> {
-    match cell_type {
-        ReferenceCellType::Interval => Ok(nderivs + 1),
-        ReferenceCellType::Triangle => Ok((nderivs + 1) * (nderivs + 2) / 2),
-        ReferenceCellType::Quadrilateral => Ok((nderivs + 1) * (nderivs + 2) / 2),
-        ReferenceCellType::Tetrahedron => Ok((nderivs + 1) * (nderivs + 2) * (nderivs + 3) / 6),
-        ReferenceCellType::Hexahedron => Ok((nderivs + 1) * (nderivs + 2) * (nderivs + 3) / 6),
-        ReferenceCellType::Prism => Ok((nderivs + 1) * (nderivs + 2) * (nderivs + 3) / 6),
-        ReferenceCellType::Pyramid => Ok((nderivs + 1) * (nderivs + 2) * (nderivs + 3) / 6),
-        _ => Err(()),
-    }
-}
-
-impl<'a, F: FiniteElement> TabulatedData<'a, F> {
-    pub fn new(element: &'a F, nderivs: usize, npoints: usize) -> Self {
-        let deriv_count = compute_derivative_count(nderivs, element.cell_type()).unwrap();
+impl TabulatedData {
+    pub fn new<F: FiniteElement + ?Sized>(element: &F, nderivs: usize, npoints: usize) -> Self {
+        let deriv_count = derivative_count(element.cell_type(), nderivs);
         let point_count = npoints;
         let basis_count = element.dim();
         let value_size = element.value_size();
         let data = vec![0.0; deriv_count * point_count * basis_count * value_size];
         Self {
             data,
-            element,
             deriv_count,
             point_count,
             basis_count,
@@ -92,7 +336,7 @@ impl<'a, F: FiniteElement> TabulatedData<'a, F> {
         self.data.get_mut(index).unwrap()
     }
 
-    pub fn get(&mut self, deriv: usize, point: usize, basis: usize, component: usize) -> &f64 {
+    pub fn get(&self, deriv: usize, point: usize, basis: usize, component: usize) -> &f64 {
         // Debug here
         let index = ((deriv * self.point_count + point) * self.basis_count + basis)
             * self.value_size
@@ -100,6 +344,36 @@ impl<'a, F: FiniteElement> TabulatedData<'a, F> {
         self.data.get(index).unwrap()
     }
 
+    /// The tabulated values, with shape (deriv_count, point_count, basis_count, value_size)
+    pub fn as_slice(&self) -> &[f64] {
+        &self.data
+    }
+    /// Consume the tabulated data and return the values, with shape (deriv_count, point_count,
+    /// basis_count, value_size)
+    pub fn into_vec(self) -> Vec<f64> {
+        self.data
+    }
+    /// Replace the values, changing the value size
+    ///
+    /// This is used when pushing forward and pulling back values between reference and physical
+    /// cells of different dimensions.
+    pub(crate) fn set_values(&mut self, value_size: usize, data: Vec<f64>) {
+        assert_eq!(
+            data.len(),
+            self.deriv_count * self.point_count * self.basis_count * value_size
+        );
+        self.value_size = value_size;
+        self.data = data;
+    }
+    /// Copy the values and shape of `other` into this data, reusing the existing allocation
+    pub(crate) fn copy_from(&mut self, other: &TabulatedData) {
+        self.deriv_count = other.deriv_count;
+        self.point_count = other.point_count;
+        self.basis_count = other.basis_count;
+        self.value_size = other.value_size;
+        self.data.clear();
+        self.data.extend_from_slice(&other.data);
+    }
     pub fn deriv_count(&self) -> usize {
         self.deriv_count
     }
@@ -117,13 +391,275 @@ impl<'a, F: FiniteElement> TabulatedData<'a, F> {
 #[cfg(test)]
 mod test {
     use crate::element::*;
+    use approx::*;
 
     #[test]
     fn test_lagrange_1() {
-        let e = LagrangeElement {
-            celltype: ReferenceCellType::Triangle,
-            degree: 1,
-        };
+        let e = LagrangeElement::new(ReferenceCellType::Triangle, 1);
         assert_eq!(e.value_size(), 1);
     }
+
+    #[test]
+    fn test_create_element() {
+        let elements = [
+            create_element(
+                ElementFamily::Lagrange,
+                ReferenceCellType::Triangle,
+                2,
+                Continuity::Continuous,
+            ),
+            create_element(
+                ElementFamily::RaviartThomas,
+                ReferenceCellType::Tetrahedron,
+                1,
+                Continuity::Continuous,
+            ),
+            create_element(
+                ElementFamily::RaviartThomas,
+                ReferenceCellType::Quadrilateral,
+                1,
+                Continuity::Continuous,
+            ),
+            create_element(
+                ElementFamily::NedelecFirstKind,
+                ReferenceCellType::Hexahedron,
+                1,
+                Continuity::Continuous,
+            ),
+            create_element(
+                ElementFamily::BrezziDouglasMarini,
+                ReferenceCellType::Triangle,
+                1,
+                Continuity::Discontinuous,
+            ),
+        ]
+        .map(|e| e.unwrap());
+        let dims = [6, 4, 4, 12, 6];
+        let value_sizes = [1, 3, 2, 3, 2];
+        for ((e, dim), value_size) in elements.iter().zip(dims).zip(value_sizes) {
+            assert_eq!(e.dim(), dim);
+            assert_eq!(e.value_size(), value_size);
+            let tdim = create_cell(e.cell_type()).dim();
+            let mut data = TabulatedData::new(e.as_ref(), 1, 1);
+            e.tabulate(&vec![0.25; tdim], 1, &mut data);
+            assert_eq!(data.basis_count(), dim);
+        }
+        assert!(!elements[0].discontinuous());
+        assert!(elements[4].discontinuous());
+        assert_eq!(elements[4].entity_dofs(1, 0), vec![]);
+        assert_eq!(elements[4].entity_dofs(2, 0), (0..6).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_create_unsupported_element() {
+        for (family, cell_type, degree) in [
+            (ElementFamily::RaviartThomas, ReferenceCellType::Prism, 1),
+            (ElementFamily::RaviartThomas, ReferenceCellType::Triangle, 0),
+            (
+                ElementFamily::NedelecFirstKind,
+                ReferenceCellType::Interval,
+                1,
+            ),
+            (
+                ElementFamily::BrezziDouglasMarini,
+                ReferenceCellType::Hexahedron,
+                1,
+            ),
+            (
+                ElementFamily::NedelecSecondKind,
+                ReferenceCellType::Pyramid,
+                1,
+            ),
+            (ElementFamily::Regge, ReferenceCellType::Quadrilateral, 1),
+            (
+                ElementFamily::HellanHerrmannJohnson,
+                ReferenceCellType::Prism,
+                0,
+            ),
+        ] {
+            for continuity in [Continuity::Continuous, Continuity::Discontinuous] {
+                assert!(create_element(family, cell_type, degree, continuity).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_entity_closure_dofs() {
+        let e = create_lagrange_element(ReferenceCellType::Triangle, 2, false);
+        assert_eq!(e.entity_closure_dofs(0, 1), vec![1]);
+        assert_eq!(e.entity_closure_dofs(1, 0), vec![1, 2, 3]);
+        assert_eq!(e.entity_closure_dofs(2, 0), (0..6).collect::<Vec<_>>());
+        assert_eq!(e.num_entity_dofs(), vec![vec![1; 3], vec![1; 3], vec![0]]);
+        assert_eq!(
+            e.num_entity_closure_dofs(),
+            vec![vec![1; 3], vec![3; 3], vec![6]]
+        );
+
+        for cell_type in [
+            ReferenceCellType::Interval,
+            ReferenceCellType::Quadrilateral,
+            ReferenceCellType::Tetrahedron,
+            ReferenceCellType::Hexahedron,
+        ] {
+            let e = create_lagrange_element(cell_type, 3, false);
+            let tdim = create_cell(cell_type).dim();
+            let mut dofs = e.entity_closure_dofs(tdim, 0);
+            dofs.sort();
+            assert_eq!(dofs, (0..e.dim()).collect::<Vec<_>>());
+        }
+
+        let e = create_nedelec_element(ReferenceCellType::Tetrahedron, 2, false);
+        let mut dofs = e.entity_closure_dofs(2, 0);
+        dofs.sort();
+        let mut expected = [0, 1, 2]
+            .iter()
+            .flat_map(|edge| e.entity_dofs(1, *edge))
+            .chain(e.entity_dofs(2, 0))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(dofs, expected);
+        assert_eq!(e.num_entity_closure_dofs()[2], vec![8; 4]);
+    }
+
+    /// Check that the interpolation matrix applied to the values of each basis function at the
+    /// interpolation points gives the DOF values of the basis function
+    fn check_interpolation_matrix(e: &(impl FiniteElement + ?Sized)) {
+        let tdim = create_cell(e.cell_type()).dim();
+        let points = e.interpolation_points();
+        let npts = points.len() / tdim;
+        let m = e.interpolation_matrix();
+        let ncols = e.value_size() * npts;
+        assert_eq!(m.len(), e.dim() * ncols);
+        let mut table = TabulatedData::new(e, 0, npts);
+        e.tabulate(&points, 0, &mut table);
+        for i in 0..e.dim() {
+            for j in 0..e.dim() {
+                let mut dof = 0.0;
+                for v in 0..e.value_size() {
+                    for p in 0..npts {
+                        dof += m[i * ncols + v * npts + p] * table.get(0, p, j, v);
+                    }
+                }
+                assert_relative_eq!(dof, if i == j { 1.0 } else { 0.0 }, epsilon = 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpolation_matrix() {
+        for (family, cell_type, degree) in [
+            (ElementFamily::Lagrange, ReferenceCellType::Triangle, 3),
+            (ElementFamily::Lagrange, ReferenceCellType::Hexahedron, 2),
+            (
+                ElementFamily::RaviartThomas,
+                ReferenceCellType::Tetrahedron,
+                2,
+            ),
+            (
+                ElementFamily::RaviartThomas,
+                ReferenceCellType::Quadrilateral,
+                2,
+            ),
+            (
+                ElementFamily::NedelecFirstKind,
+                ReferenceCellType::Triangle,
+                2,
+            ),
+            (
+                ElementFamily::BrezziDouglasMarini,
+                ReferenceCellType::Triangle,
+                2,
+            ),
+            (
+                ElementFamily::NedelecSecondKind,
+                ReferenceCellType::Tetrahedron,
+                2,
+            ),
+            (ElementFamily::Regge, ReferenceCellType::Triangle, 1),
+            (
+                ElementFamily::HellanHerrmannJohnson,
+                ReferenceCellType::Triangle,
+                1,
+            ),
+        ] {
+            for continuity in [Continuity::Continuous, Continuity::Discontinuous] {
+                check_interpolation_matrix(
+                    create_element(family, cell_type, degree, continuity)
+                        .unwrap()
+                        .as_ref(),
+                );
+            }
+        }
+        check_interpolation_matrix(&LagrangeElement::new(ReferenceCellType::Prism, 2));
+        check_interpolation_matrix(&LagrangeElementIntervalDegree0 {});
+        check_interpolation_matrix(&LagrangeElementIntervalDegree1 {});
+        check_interpolation_matrix(&LagrangeElementTriangleDegree0 {});
+        check_interpolation_matrix(&LagrangeElementTriangleDegree1 {});
+        check_interpolation_matrix(&LagrangeElementQuadrilateralDegree0 {});
+        check_interpolation_matrix(&LagrangeElementQuadrilateralDegree1 {});
+        check_interpolation_matrix(&RaviartThomasElementTriangleDegree1 {});
+    }
+
+    #[test]
+    fn test_interpolate_vector_function() {
+        // The function (1 + y, 2x) is in the span of the degree 2 Nédélec element, so it is
+        // reproduced by interpolation
+        let e = create_nedelec_element(ReferenceCellType::Triangle, 2, false);
+        let points = e.interpolation_points();
+        let npts = points.len() / 2;
+        let mut f = vec![0.0; 2 * npts];
+        for (p, x) in points.chunks(2).enumerate() {
+            f[p] = 1.0 + x[1];
+            f[npts + p] = 2.0 * x[0];
+        }
+        let m = e.interpolation_matrix();
+        let dofs = (0..e.dim())
+            .map(|i| {
+                (0..2 * npts)
+                    .map(|j| m[i * 2 * npts + j] * f[j])
+                    .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut table = TabulatedData::new(&e, 0, 1);
+        e.tabulate(&[0.2, 0.3], 0, &mut table);
+        for (v, expected) in [1.3, 0.4].iter().enumerate() {
+            let value = (0..e.dim())
+                .map(|i| dofs[i] * table.get(0, 0, i, v))
+                .sum::<f64>();
+            assert_relative_eq!(value, expected, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_lagrange_ciarlet() {
+        // The Lagrange element created as a Ciarlet element agrees with LagrangeElement
+        let e = create_element(
+            ElementFamily::Lagrange,
+            ReferenceCellType::Tetrahedron,
+            3,
+            Continuity::Continuous,
+        )
+        .unwrap();
+        let e1 = LagrangeElement::new(ReferenceCellType::Tetrahedron, 3);
+        let points = vec![0.1, 0.2, 0.3, 0.5, 0.0, 0.25, 0.0, 0.0, 1.0];
+        let mut data = TabulatedData::new(e.as_ref(), 1, 3);
+        e.tabulate(&points, 1, &mut data);
+        let mut data1 = TabulatedData::new(&e1, 1, 3);
+        e1.tabulate(&points, 1, &mut data1);
+        for deriv in 0..4 {
+            for pt in 0..3 {
+                for fun in 0..e1.dim() {
+                    assert_relative_eq!(
+                        *data.get(deriv, pt, fun, 0),
+                        *data1.get(deriv, pt, fun, 0),
+                        epsilon = 1e-10
+                    );
+                }
+            }
+        }
+        for dim in 0..4 {
+            assert_eq!(e.entity_dofs(dim, 0), e1.entity_dofs(dim, 0));
+        }
+    }
 }
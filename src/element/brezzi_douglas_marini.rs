@@ -0,0 +1,126 @@
+//! Brezzi-Douglas-Marini elements
+
+use crate::element::moments::*;
+use crate::element::*;
+use crate::polyset::*;
+
+/// Create a Brezzi-Douglas-Marini element of the given degree on a triangle or tetrahedron
+///
+/// The polynomial space is P_k^d. The DOFs are integral moments of the normal component against
+/// degree k polynomials on each facet, and integral moments against degree k-1 Nédélec (first
+/// kind) elements on the interior.
+pub fn create_brezzi_douglas_marini_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    if cell_type != ReferenceCellType::Triangle && cell_type != ReferenceCellType::Tetrahedron {
+        unimplemented!(
+            "Brezzi-Douglas-Marini elements are only implemented on triangles and tetrahedra"
+        );
+    }
+    assert!(degree >= 1);
+    let tdim = create_cell(cell_type).dim();
+    let pdim = polyset_dim(cell_type, degree);
+    let mut wcoeffs = vec![0.0; tdim * pdim * tdim * pdim];
+    for i in 0..tdim * pdim {
+        wcoeffs[i * tdim * pdim + i] = 1.0;
+    }
+
+    let mut x = empty_dofs(cell_type);
+    let mut m = empty_dofs(cell_type);
+    (x[tdim - 1], m[tdim - 1]) = make_normal_integral_moments(cell_type, degree, degree);
+    if degree > 1 {
+        let interior_element = create_nedelec_element(cell_type, degree - 1, true);
+        (x[tdim], m[tdim]) = make_dot_integral_moments(cell_type, tdim, &interior_element, degree);
+    }
+
+    CiarletElement::create(
+        ElementFamily::BrezziDouglasMarini,
+        cell_type,
+        degree,
+        vec![tdim],
+        &wcoeffs,
+        x,
+        m,
+        MapType::ContravariantPiola,
+        discontinuous,
+        degree,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::element::*;
+    use approx::*;
+
+    fn check_dofs(e: &impl FiniteElement) {
+        let cell = create_cell(e.cell_type());
+        let mut ndofs = 0;
+        for dim in 0..cell.dim() + 1 {
+            for entity in 0..cell.entity_count(dim).unwrap() {
+                ndofs += e.entity_dofs(dim, entity).len();
+            }
+        }
+        assert_eq!(ndofs, e.dim());
+    }
+
+    #[test]
+    fn test_brezzi_douglas_marini_dims() {
+        for degree in 1..5 {
+            let e =
+                create_brezzi_douglas_marini_element(ReferenceCellType::Triangle, degree, false);
+            assert_eq!(e.dim(), (degree + 1) * (degree + 2));
+            assert_eq!(e.map_type(), MapType::ContravariantPiola);
+            assert_eq!(e.family(), ElementFamily::BrezziDouglasMarini);
+            for edge in 0..3 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree + 1);
+            }
+            check_dofs(&e);
+
+            let e =
+                create_brezzi_douglas_marini_element(ReferenceCellType::Tetrahedron, degree, false);
+            assert_eq!(e.dim(), (degree + 1) * (degree + 2) * (degree + 3) / 2);
+            for face in 0..4 {
+                assert_eq!(
+                    e.entity_dofs(2, face).len(),
+                    (degree + 1) * (degree + 2) / 2
+                );
+            }
+            check_dofs(&e);
+        }
+    }
+
+    #[test]
+    fn test_brezzi_douglas_marini_normal_continuity() {
+        // The normal component of a basis function on a facet is zero unless the function is
+        // associated with that facet
+        let points = [
+            [0.2, 0.3, 0.5],
+            [0.0, 0.3, 0.1],
+            [0.3, 0.0, 0.6],
+            [0.2, 0.5, 0.0],
+        ];
+        let normals = [
+            [1.0, 1.0, 1.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ];
+        for degree in 1..4 {
+            let e =
+                create_brezzi_douglas_marini_element(ReferenceCellType::Tetrahedron, degree, false);
+            let mut data = TabulatedData::new(&e, 0, 4);
+            e.tabulate(&points.concat(), 0, &mut data);
+            for (f, n) in normals.iter().enumerate() {
+                let dofs = e.entity_dofs(2, f);
+                for fun in 0..e.dim() {
+                    if !dofs.contains(&fun) {
+                        let normal = (0..3).map(|v| data.get(0, f, fun, v) * n[v]).sum::<f64>();
+                        assert_relative_eq!(normal, 0.0, epsilon = 1e-10);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! Ciarlet finite elements
+
+use crate::element::moments::sub_entity_type;
+use crate::element::transformations::*;
+use crate::element::*;
+use crate::linalg::inverse;
+use crate::polyset::*;
+
+/// A Ciarlet finite element
+///
+/// The element is defined by a polynomial space, given by coefficients against the orthonormal
+/// polynomials in [crate::polyset], and a set of DOF functionals. Each DOF functional is
+/// associated with a sub-entity of the cell and is defined by a set of interpolation points and
+/// weights: the functional applied to a function f is the sum over the points and components of
+/// the weights multiplied by the components of f at the points.
+pub struct CiarletElement {
+    cell_type: ReferenceCellType,
+    family: ElementFamily,
+    degree: usize,
+    highest_degree: usize,
+    map_type: MapType,
+    value_shape: Vec<usize>,
+    value_size: usize,
+    discontinuous: bool,
+    dim: usize,
+    coefficients: Vec<f64>,
+    entity_dofs: [Vec<Vec<usize>>; 4],
+    interpolation_points: [Vec<Vec<f64>>; 4],
+    interpolation_weights: [Vec<Vec<f64>>; 4],
+    entity_transformations: [Vec<Vec<Vec<f64>>>; 4],
+}
+
+impl CiarletElement {
+    /// Create a Ciarlet element
+    ///
+    /// `polynomial_coeffs` gives the coefficients of a basis of the element's polynomial space
+    /// against the orthonormal polynomials of degree `highest_degree` on the cell: the coefficient
+    /// of component `v` of basis function `i` against the orthonormal polynomial `k` is
+    /// `polynomial_coeffs[(i * value_size + v) * polyset_dim + k]`.
+    ///
+    /// `interpolation_points[d][e]` gives the points used to define the DOFs associated with
+    /// entity `e` of dimension `d`, with the first tdim components being the first point, the next
+    /// tdim the second point, and so on. `interpolation_weights[d][e]` gives the weights of these
+    /// DOFs: the weight for DOF `i` associated with this entity, component `v` and point `p` is
+    /// `interpolation_weights[d][e][(i * value_size + v) * npoints + p]`.
+    ///
+    /// The DOFs are numbered in the order they are given, starting with the DOFs associated with
+    /// vertices. If the element is discontinuous, all DOFs are associated with the interior of
+    /// the cell.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        family: ElementFamily,
+        cell_type: ReferenceCellType,
+        degree: usize,
+        value_shape: Vec<usize>,
+        polynomial_coeffs: &[f64],
+        interpolation_points: [Vec<Vec<f64>>; 4],
+        interpolation_weights: [Vec<Vec<f64>>; 4],
+        map_type: MapType,
+        discontinuous: bool,
+        highest_degree: usize,
+    ) -> Self {
+        let tdim = create_cell(cell_type).dim();
+        let value_size = value_shape.iter().product();
+        let pdim = polyset_dim(cell_type, highest_degree);
+        let dim = polynomial_coeffs.len() / (value_size * pdim);
+        assert_eq!(polynomial_coeffs.len(), dim * value_size * pdim);
+
+        let mut entity_dofs = [vec![], vec![], vec![], vec![]];
+        let mut dof = 0;
+        for d in 0..4 {
+            assert_eq!(
+                interpolation_points[d].len(),
+                interpolation_weights[d].len()
+            );
+            for (points, weights) in interpolation_points[d]
+                .iter()
+                .zip(interpolation_weights[d].iter())
+            {
+                let npts = points.len() / tdim;
+                let ndofs = if npts == 0 {
+                    0
+                } else {
+                    weights.len() / (value_size * npts)
+                };
+                assert_eq!(weights.len(), ndofs * value_size * npts);
+                entity_dofs[d].push((dof..dof + ndofs).collect::<Vec<_>>());
+                dof += ndofs;
+            }
+        }
+        assert_eq!(dof, dim);
+
+        // Compute the dual matrix: the DOF functionals applied to the spanning functions
+        let mut dual_matrix = vec![0.0; dim * dim];
+        let mut dof = 0;
+        for d in 0..4 {
+            for (points, weights) in interpolation_points[d]
+                .iter()
+                .zip(interpolation_weights[d].iter())
+            {
+                let npts = points.len() / tdim;
+                if npts == 0 {
+                    continue;
+                }
+                let ndofs = weights.len() / (value_size * npts);
+                let mut table = vec![0.0; npts * pdim];
+                tabulate_legendre_polynomials(cell_type, points, highest_degree, 0, &mut table);
+                for i in 0..ndofs {
+                    for j in 0..dim {
+                        let mut entry = 0.0;
+                        for v in 0..value_size {
+                            for p in 0..npts {
+                                let w = weights[(i * value_size + v) * npts + p];
+                                if w != 0.0 {
+                                    let offset = (j * value_size + v) * pdim;
+                                    entry += w
+                                        * (0..pdim)
+                                            .map(|k| {
+                                                polynomial_coeffs[offset + k] * table[p * pdim + k]
+                                            })
+                                            .sum::<f64>();
+                                }
+                            }
+                        }
+                        dual_matrix[(dof + i) * dim + j] = entry;
+                    }
+                }
+                dof += ndofs;
+            }
+        }
+
+        // The basis function i is sum_j A_ij psi_j, where psi_j are the spanning functions and
+        // A is the inverse of the transpose of the dual matrix
+        let dual_inverse = inverse(&dual_matrix, dim);
+        let mut coefficients = vec![0.0; dim * value_size * pdim];
+        for i in 0..dim {
+            for j in 0..dim {
+                let a = dual_inverse[j * dim + i];
+                if a != 0.0 {
+                    for k in 0..value_size * pdim {
+                        coefficients[i * value_size * pdim + k] +=
+                            a * polynomial_coeffs[j * value_size * pdim + k];
+                    }
+                }
+            }
+        }
+
+        if discontinuous {
+            for dofs in entity_dofs.iter_mut().take(tdim) {
+                for e in dofs.iter_mut() {
+                    e.clear();
+                }
+            }
+            entity_dofs[tdim] = vec![(0..dim).collect()];
+        }
+
+        let mut element = Self {
+            cell_type,
+            family,
+            degree,
+            highest_degree,
+            map_type,
+            value_shape,
+            value_size,
+            discontinuous,
+            dim,
+            coefficients,
+            entity_dofs,
+            interpolation_points,
+            interpolation_weights,
+            entity_transformations: [vec![], vec![], vec![], vec![]],
+        };
+        let cell = create_cell(cell_type);
+        for d in 1..usize::min(tdim, 3) {
+            element.entity_transformations[d] = (0..cell.entity_count(d).unwrap())
+                .map(|e| {
+                    (0..transformation_count(sub_entity_type(cell_type, d, e)))
+                        .map(|t| compute_entity_transformation(&element, d, e, t))
+                        .collect()
+                })
+                .collect();
+        }
+        element
+    }
+
+    /// The shape of the values of the basis functions
+    pub fn value_shape(&self) -> &[usize] {
+        &self.value_shape
+    }
+
+    /// The points used to define the DOF functionals, for each sub-entity
+    pub fn entity_interpolation_points(&self) -> &[Vec<Vec<f64>>; 4] {
+        &self.interpolation_points
+    }
+
+    /// The weights used to define the DOF functionals, for each sub-entity
+    pub fn entity_interpolation_weights(&self) -> &[Vec<Vec<f64>>; 4] {
+        &self.interpolation_weights
+    }
+}
+
+impl FiniteElement for CiarletElement {
+    fn cell_type(&self) -> ReferenceCellType {
+        self.cell_type
+    }
+    fn value_size(&self) -> usize {
+        self.value_size
+    }
+    fn map_type(&self) -> MapType {
+        self.map_type
+    }
+    fn degree(&self) -> usize {
+        self.degree
+    }
+    fn highest_degree(&self) -> usize {
+        self.highest_degree
+    }
+    fn family(&self) -> ElementFamily {
+        self.family
+    }
+    fn discontinuous(&self) -> bool {
+        self.discontinuous
+    }
+    fn dim(&self) -> usize {
+        self.dim
+    }
+    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData) {
+        let tdim = create_cell(self.cell_type).dim();
+        let npts = points.len() / tdim;
+        let pdim = polyset_dim(self.cell_type, self.highest_degree);
+        let nderiv_count = derivative_count(self.cell_type, nderivs);
+        assert_eq!(data.deriv_count(), nderiv_count);
+
+        let mut table = vec![0.0; nderiv_count * npts * pdim];
+        tabulate_legendre_polynomials(
+            self.cell_type,
+            points,
+            self.highest_degree,
+            nderivs,
+            &mut table,
+        );
+        for deriv in 0..nderiv_count {
+            for pt in 0..npts {
+                let values = &table[(deriv * npts + pt) * pdim..(deriv * npts + pt + 1) * pdim];
+                for fun in 0..self.dim {
+                    for v in 0..self.value_size {
+                        let offset = (fun * self.value_size + v) * pdim;
+                        *data.get_mut(deriv, pt, fun, v) = values
+                            .iter()
+                            .zip(self.coefficients[offset..offset + pdim].iter())
+                            .map(|(p, c)| p * c)
+                            .sum();
+                    }
+                }
+            }
+        }
+    }
+    fn entity_dofs(&self, entity_dim: usize, entity_number: usize) -> Vec<usize> {
+        self.entity_dofs[entity_dim][entity_number].clone()
+    }
+    fn entity_transformation(
+        &self,
+        entity_dim: usize,
+        entity_number: usize,
+        transformation: usize,
+    ) -> Vec<f64> {
+        self.entity_transformations[entity_dim][entity_number][transformation].clone()
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        self.interpolation_points
+            .iter()
+            .flat_map(|points| points.iter().flatten().copied())
+            .collect()
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        let tdim = create_cell(self.cell_type).dim();
+        let npts_total = self.interpolation_points().len() / tdim;
+        let ncols = self.value_size * npts_total;
+        let mut matrix = vec![0.0; self.dim * ncols];
+        // The DOFs and the points are both numbered in sub-entity order
+        let mut dof = 0;
+        let mut first_point = 0;
+        for (points, weights) in self
+            .interpolation_points
+            .iter()
+            .flatten()
+            .zip(self.interpolation_weights.iter().flatten())
+        {
+            let npts = points.len() / tdim;
+            if npts == 0 {
+                continue;
+            }
+            let ndofs = weights.len() / (self.value_size * npts);
+            for i in 0..ndofs {
+                for v in 0..self.value_size {
+                    for p in 0..npts {
+                        matrix[(dof + i) * ncols + v * npts_total + first_point + p] =
+                            weights[(i * self.value_size + v) * npts + p];
+                    }
+                }
+            }
+            dof += ndofs;
+            first_point += npts;
+        }
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::element::*;
+    use crate::polyset::*;
+    use crate::quadrature::*;
+    use approx::*;
+
+    /// Compute the coefficients of a function against the orthonormal polynomials on a cell
+    fn project(
+        cell_type: ReferenceCellType,
+        degree: usize,
+        f: impl Fn(&[f64]) -> Vec<f64>,
+        value_size: usize,
+    ) -> Vec<f64> {
+        let rule = make_quadrature(cell_type, 2 * degree);
+        let pdim = polyset_dim(cell_type, degree);
+        let mut table = vec![0.0; rule.npoints * pdim];
+        tabulate_legendre_polynomials(cell_type, &rule.points, degree, 0, &mut table);
+        let mut coeffs = vec![0.0; value_size * pdim];
+        for p in 0..rule.npoints {
+            let value = f(&rule.points[p * rule.dim..(p + 1) * rule.dim]);
+            for v in 0..value_size {
+                for k in 0..pdim {
+                    coeffs[v * pdim + k] += rule.weights[p] * value[v] * table[p * pdim + k];
+                }
+            }
+        }
+        coeffs
+    }
+
+    #[test]
+    fn test_custom_lagrange_1_triangle() {
+        let pdim = polyset_dim(ReferenceCellType::Triangle, 1);
+        let mut coeffs = vec![0.0; 3 * pdim];
+        for i in 0..3 {
+            coeffs[i * pdim + i] = 1.0;
+        }
+        let e = CiarletElement::create(
+            ElementFamily::Lagrange,
+            ReferenceCellType::Triangle,
+            1,
+            vec![],
+            &coeffs,
+            [
+                vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.0, 1.0]],
+                vec![vec![], vec![], vec![]],
+                vec![vec![]],
+                vec![],
+            ],
+            [
+                vec![vec![1.0], vec![1.0], vec![1.0]],
+                vec![vec![], vec![], vec![]],
+                vec![vec![]],
+                vec![],
+            ],
+            MapType::Identity,
+            false,
+            1,
+        );
+        assert_eq!(e.dim(), 3);
+        assert_eq!(e.value_size(), 1);
+        for v in 0..3 {
+            assert_eq!(e.entity_dofs(0, v), vec![v]);
+        }
+
+        let points = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.0, 0.0, 0.5, 0.3, 0.2];
+        let mut data = TabulatedData::new(&e, 1, 6);
+        e.tabulate(&points, 1, &mut data);
+
+        let e1 = LagrangeElementTriangleDegree1 {};
+        let mut data1 = TabulatedData::new(&e1, 1, 6);
+        e1.tabulate(&points, 1, &mut data1);
+
+        for deriv in 0..3 {
+            for pt in 0..6 {
+                for fun in 0..3 {
+                    assert_relative_eq!(
+                        *data.get(deriv, pt, fun, 0),
+                        *data1.get(deriv, pt, fun, 0),
+                        epsilon = 1e-12
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_raviart_thomas_1_triangle() {
+        // The polynomial space is spanned by (1, 0), (0, 1) and (x, y)
+        let mut coeffs = vec![];
+        coeffs.extend(project(
+            ReferenceCellType::Triangle,
+            1,
+            |_| vec![1.0, 0.0],
+            2,
+        ));
+        coeffs.extend(project(
+            ReferenceCellType::Triangle,
+            1,
+            |_| vec![0.0, 1.0],
+            2,
+        ));
+        coeffs.extend(project(
+            ReferenceCellType::Triangle,
+            1,
+            |p| vec![p[0], p[1]],
+            2,
+        ));
+
+        // The DOFs are normal components at the midpoints of the edges
+        let e = CiarletElement::create(
+            ElementFamily::RaviartThomas,
+            ReferenceCellType::Triangle,
+            1,
+            vec![2],
+            &coeffs,
+            [
+                vec![vec![], vec![], vec![]],
+                vec![vec![0.5, 0.5], vec![0.0, 0.5], vec![0.5, 0.0]],
+                vec![vec![]],
+                vec![],
+            ],
+            [
+                vec![vec![], vec![], vec![]],
+                vec![vec![-1.0, -1.0], vec![-1.0, 0.0], vec![0.0, 1.0]],
+                vec![vec![]],
+                vec![],
+            ],
+            MapType::ContravariantPiola,
+            false,
+            1,
+        );
+        assert_eq!(e.dim(), 3);
+        assert_eq!(e.value_size(), 2);
+        assert_eq!(e.value_shape(), [2]);
+        for edge in 0..3 {
+            assert_eq!(e.entity_dofs(1, edge), vec![edge]);
+        }
+
+        let points = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.0, 0.0, 0.5, 0.3, 0.2];
+        let mut data = TabulatedData::new(&e, 1, 6);
+        e.tabulate(&points, 1, &mut data);
+
+        let e1 = RaviartThomasElementTriangleDegree1 {};
+        let mut data1 = TabulatedData::new(&e1, 1, 6);
+        e1.tabulate(&points, 1, &mut data1);
+
+        for deriv in 0..3 {
+            for pt in 0..6 {
+                for fun in 0..3 {
+                    for v in 0..2 {
+                        assert_relative_eq!(
+                            *data.get(deriv, pt, fun, v),
+                            *data1.get(deriv, pt, fun, v),
+                            epsilon = 1e-12
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_discontinuous() {
+        let pdim = polyset_dim(ReferenceCellType::Interval, 1);
+        let mut coeffs = vec![0.0; 2 * pdim];
+        for i in 0..2 {
+            coeffs[i * pdim + i] = 1.0;
+        }
+        let e = CiarletElement::create(
+            ElementFamily::Lagrange,
+            ReferenceCellType::Interval,
+            1,
+            vec![],
+            &coeffs,
+            [vec![vec![0.0], vec![1.0]], vec![vec![]], vec![], vec![]],
+            [vec![vec![1.0], vec![1.0]], vec![vec![]], vec![], vec![]],
+            MapType::Identity,
+            true,
+            1,
+        );
+        assert!(e.discontinuous());
+        assert_eq!(e.entity_dofs(0, 0), vec![]);
+        assert_eq!(e.entity_dofs(0, 1), vec![]);
+        assert_eq!(e.entity_dofs(1, 0), vec![0, 1]);
+    }
+}
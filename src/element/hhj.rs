@@ -0,0 +1,183 @@
+//! Hellan-Herrmann-Johnson elements
+
+use crate::element::moments::*;
+use crate::element::regge::symmetric_matrix_span;
+use crate::element::*;
+use crate::linalg::orthonormal_row_basis;
+use crate::polyset::*;
+use crate::quadrature::*;
+
+/// Create a Hellan-Herrmann-Johnson element of the given degree on a triangle or tetrahedron
+///
+/// The polynomial space is the space of symmetric matrices whose entries are degree k
+/// polynomials. The DOFs on each facet are integral moments of n^T S n against degree k
+/// polynomials, where n is the normal to the facet. The DOFs on the interior are integral moments
+/// against an orthonormal basis of the functions in the space whose normal-normal components
+/// vanish on every facet.
+pub fn create_hhj_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    if cell_type != ReferenceCellType::Triangle && cell_type != ReferenceCellType::Tetrahedron {
+        unimplemented!(
+            "Hellan-Herrmann-Johnson elements are only implemented on triangles and tetrahedra"
+        );
+    }
+    let tdim = create_cell(cell_type).dim();
+    let value_size = tdim * tdim;
+    let pdim = polyset_dim(cell_type, degree);
+    let ncoeffs = value_size * pdim;
+    let wcoeffs = symmetric_matrix_span(cell_type, degree);
+    let dim = wcoeffs.len() / ncoeffs;
+
+    let mut x = empty_dofs(cell_type);
+    let mut m = empty_dofs(cell_type);
+    (x[tdim - 1], m[tdim - 1]) =
+        make_integral_moments(cell_type, tdim - 1, degree, degree, value_size, |f, _| {
+            let n = facet_normal(cell_type, f);
+            vec![(0..value_size).map(|v| n[v / tdim] * n[v % tdim]).collect()]
+        });
+
+    // Represent each facet DOF functional as a vector in the coefficient space, projected onto
+    // the polynomial space
+    let mut functionals = vec![];
+    for (points, weights) in x[tdim - 1].iter().zip(m[tdim - 1].iter()) {
+        let npts = points.len() / tdim;
+        let mut table = vec![0.0; npts * pdim];
+        tabulate_legendre_polynomials(cell_type, points, degree, 0, &mut table);
+        for dof_weights in weights.chunks(value_size * npts) {
+            let mut r = vec![0.0; ncoeffs];
+            for v in 0..value_size {
+                for p in 0..npts {
+                    for k in 0..pdim {
+                        r[v * pdim + k] += dof_weights[v * npts + p] * table[p * pdim + k];
+                    }
+                }
+            }
+            let mut projected = vec![0.0; ncoeffs];
+            for w in wcoeffs.chunks(ncoeffs) {
+                let dot = r.iter().zip(w).map(|(a, b)| a * b).sum::<f64>();
+                for (pr, wi) in projected.iter_mut().zip(w) {
+                    *pr += dot * wi;
+                }
+            }
+            functionals.extend(projected);
+        }
+    }
+    let nfacet_dofs = functionals.len() / ncoeffs;
+
+    // The interior test functions span the orthogonal complement of the functionals in the
+    // polynomial space
+    let mut combined = orthonormal_row_basis(&functionals, ncoeffs);
+    assert_eq!(combined.len(), nfacet_dofs * ncoeffs);
+    combined.extend(&wcoeffs);
+    let combined = orthonormal_row_basis(&combined, ncoeffs);
+    let interior = &combined[nfacet_dofs * ncoeffs..];
+    assert_eq!(interior.len(), (dim - nfacet_dofs) * ncoeffs);
+
+    // There are no interior DOFs if the facet DOFs span the whole space
+    if dim > nfacet_dofs {
+        let rule = make_quadrature(cell_type, 2 * degree);
+        let mut table = vec![0.0; rule.npoints * pdim];
+        tabulate_legendre_polynomials(cell_type, &rule.points, degree, 0, &mut table);
+        let mut weights = vec![0.0; interior.len() / pdim * rule.npoints];
+        for (dof, q) in interior.chunks(ncoeffs).enumerate() {
+            for v in 0..value_size {
+                for p in 0..rule.npoints {
+                    weights[(dof * value_size + v) * rule.npoints + p] = rule.weights[p]
+                        * (0..pdim)
+                            .map(|k| q[v * pdim + k] * table[p * pdim + k])
+                            .sum::<f64>();
+                }
+            }
+        }
+        x[tdim] = vec![rule.points];
+        m[tdim] = vec![weights];
+    }
+
+    CiarletElement::create(
+        ElementFamily::HellanHerrmannJohnson,
+        cell_type,
+        degree,
+        vec![tdim, tdim],
+        &wcoeffs,
+        x,
+        m,
+        MapType::DoubleContravariantPiola,
+        discontinuous,
+        degree,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::element::moments::*;
+    use crate::element::*;
+    use approx::*;
+
+    #[test]
+    fn test_hhj_dims() {
+        for degree in 0..4 {
+            let e = create_hhj_element(ReferenceCellType::Triangle, degree, false);
+            assert_eq!(e.dim(), 3 * (degree + 1) * (degree + 2) / 2);
+            assert_eq!(e.value_size(), 4);
+            assert_eq!(e.map_type(), MapType::DoubleContravariantPiola);
+            assert_eq!(e.family(), ElementFamily::HellanHerrmannJohnson);
+            for edge in 0..3 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree + 1);
+            }
+            assert_eq!(e.entity_dofs(2, 0).len(), 3 * degree * (degree + 1) / 2);
+            if degree == 0 {
+                // Only the facet quadrature points are used
+                let nfacet_points = e.entity_interpolation_points()[1]
+                    .iter()
+                    .map(|p| p.len() / 2)
+                    .sum::<usize>();
+                assert_eq!(e.interpolation_points().len() / 2, nfacet_points);
+            }
+
+            let e = create_hhj_element(ReferenceCellType::Tetrahedron, degree, false);
+            assert_eq!(e.dim(), (degree + 1) * (degree + 2) * (degree + 3));
+            assert_eq!(e.value_size(), 9);
+            for face in 0..4 {
+                assert_eq!(
+                    e.entity_dofs(2, face).len(),
+                    (degree + 1) * (degree + 2) / 2
+                );
+            }
+            assert_eq!(e.entity_dofs(1, 0).len(), 0);
+        }
+    }
+
+    #[test]
+    fn test_hhj_normal_continuity() {
+        for cell_type in [ReferenceCellType::Triangle, ReferenceCellType::Tetrahedron] {
+            let cell = create_cell(cell_type);
+            let tdim = cell.dim();
+            let nfacets = cell.entity_count(tdim - 1).unwrap();
+            let mut points = vec![];
+            for f in 0..nfacets {
+                let (origin, axes) = sub_entity_geometry(cell_type, tdim - 1, f);
+                points.extend(map_points(&origin, &axes, &[0.3, 0.2][..tdim - 1]));
+            }
+            for degree in 0..3 {
+                let e = create_hhj_element(cell_type, degree, false);
+                let mut data = TabulatedData::new(&e, 0, nfacets);
+                e.tabulate(&points, 0, &mut data);
+                for f in 0..nfacets {
+                    let n = facet_normal(cell_type, f);
+                    let dofs = e.entity_dofs(tdim - 1, f);
+                    for fun in 0..e.dim() {
+                        if !dofs.contains(&fun) {
+                            let nn = (0..tdim * tdim)
+                                .map(|v| data.get(0, f, fun, v) * n[v / tdim] * n[v % tdim])
+                                .sum::<f64>();
+                            assert_relative_eq!(nn, 0.0, epsilon = 1e-10);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
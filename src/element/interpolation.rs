@@ -0,0 +1,253 @@
+//! Interpolation between finite elements
+
+use crate::element::*;
+
+/// Compute the matrix that interpolates functions in one element into another element on the same
+/// cell
+///
+/// The returned row-major matrix maps the coefficients of a function in `element_from` to the
+/// coefficients of its interpolant in `element_to`. If the elements have the same value size, the
+/// matrix is `element_to.dim()` by `element_from.dim()`. If `element_from` has value size 1 and
+/// `element_to` has value size n > 1, the matrix interpolates from the blocked element made of n
+/// copies of `element_from`, whose coefficient `j * n + c` is component c of basis function j, so
+/// the matrix is `element_to.dim()` by `n * element_from.dim()`. Similarly, if `element_to` has
+/// value size 1 and `element_from` has value size n > 1, the matrix interpolates into the blocked
+/// element made of n copies of `element_to`, and is `n * element_to.dim()` by
+/// `element_from.dim()`.
+///
+/// The matrix is computed on the reference cell. If the elements use the same map, it also gives
+/// the interpolation on physical cells. If `element_from` uses the identity map (as does a blocked
+/// scalar element) and `element_to` uses a different map, such as a Piola map, the matrix
+/// interpolates functions on the reference cell. On an affine physical cell, where the Jacobian
+/// is constant, the interpolation is given by pulling back the values at each node of
+/// `element_from` using the map of `element_to` before applying the matrix; on non-affine cells,
+/// the matrix does not give the interpolation.
+///
+/// An error is returned if the cell types differ, if the map types differ and `element_from`
+/// does not use the identity map, or if the value sizes are not compatible.
+#[allow(clippy::result_unit_err)]
+pub fn compute_interpolation_operator<F1: FiniteElement + ?Sized, F2: FiniteElement + ?Sized>(
+    element_from: &F1,
+    element_to: &F2,
+) -> Result<Vec<f64>, ()> {
+    if element_from.cell_type() != element_to.cell_type()
+        || (element_from.map_type() != element_to.map_type()
+            && element_from.map_type() != MapType::Identity)
+    {
+        return Err(());
+    }
+    let value_size_from = element_from.value_size();
+    let value_size_to = element_to.value_size();
+    // The block sizes of the blocked elements, if any
+    let (block_from, block_to) = if value_size_from == value_size_to {
+        (1, 1)
+    } else if value_size_from == 1 {
+        (value_size_to, 1)
+    } else if value_size_to == 1 {
+        (1, value_size_from)
+    } else {
+        return Err(());
+    };
+
+    let tdim = create_cell(element_to.cell_type()).dim();
+    let points = element_to.interpolation_points();
+    let npts = points.len() / tdim;
+    let matrix = element_to.interpolation_matrix();
+    let mut table = TabulatedData::new(element_from, 0, npts);
+    element_from.tabulate(&points, 0, &mut table);
+
+    let nrows = block_to * element_to.dim();
+    let ncols = block_from * element_from.dim();
+    let block_size = usize::max(block_from, block_to);
+    let m = |i: usize, v: usize, p: usize| matrix[(i * value_size_to + v) * npts + p];
+    let mut operator = vec![0.0; nrows * ncols];
+    for i in 0..element_to.dim() {
+        for j in 0..element_from.dim() {
+            for c in 0..block_size {
+                let entry = if block_size == 1 {
+                    (0..value_size_to)
+                        .map(|v| {
+                            (0..npts)
+                                .map(|p| m(i, v, p) * table.get(0, p, j, v))
+                                .sum::<f64>()
+                        })
+                        .sum()
+                } else if block_from > 1 {
+                    // Component c of the blocked element is paired with component c of element_to
+                    (0..npts).map(|p| m(i, c, p) * table.get(0, p, j, 0)).sum()
+                } else {
+                    // Component c of the blocked element is paired with component c of element_from
+                    (0..npts).map(|p| m(i, 0, p) * table.get(0, p, j, c)).sum()
+                };
+                let row = if block_to > 1 { i * block_to + c } else { i };
+                let col = if block_from > 1 {
+                    j * block_from + c
+                } else {
+                    j
+                };
+                operator[row * ncols + col] = entry;
+            }
+        }
+    }
+    Ok(operator)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::element::*;
+    use crate::polyset::*;
+    use approx::*;
+
+    /// Evaluate the function with the given coefficients in an element at a point
+    fn evaluate(e: &impl FiniteElement, coefficients: &[f64], point: &[f64]) -> Vec<f64> {
+        let mut table = TabulatedData::new(e, 0, 1);
+        e.tabulate(point, 0, &mut table);
+        (0..e.value_size())
+            .map(|v| {
+                (0..e.dim())
+                    .map(|i| coefficients[i] * table.get(0, 0, i, v))
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Apply a row-major matrix with `ncols` columns to a vector
+    fn apply(matrix: &[f64], ncols: usize, x: &[f64]) -> Vec<f64> {
+        matrix
+            .chunks(ncols)
+            .map(|row| row.iter().zip(x).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    #[test]
+    fn test_lagrange_interpolation() {
+        let p1 = create_lagrange_element(ReferenceCellType::Triangle, 1, false);
+        let p2 = create_lagrange_element(ReferenceCellType::Triangle, 2, false);
+        let up = compute_interpolation_operator(&p1, &p2).unwrap();
+        let down = compute_interpolation_operator(&p2, &p1).unwrap();
+        assert_eq!(up.len(), 6 * 3);
+        assert_eq!(down.len(), 3 * 6);
+
+        // A degree 1 function is represented exactly in the degree 2 space
+        let coefficients = [0.5, -1.0, 2.0];
+        let coefficients2 = apply(&up, 3, &coefficients);
+        for point in [[0.2, 0.3], [0.7, 0.1]] {
+            assert_relative_eq!(
+                evaluate(&p2, &coefficients2, &point)[0],
+                evaluate(&p1, &coefficients, &point)[0],
+                epsilon = 1e-12
+            );
+        }
+        // Interpolating back gives the original coefficients
+        for (a, b) in apply(&down, 6, &coefficients2).iter().zip(coefficients) {
+            assert_relative_eq!(*a, b, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_vector_interpolation() {
+        // The RT space of degree 1 is contained in the BDM space of degree 1
+        let rt = create_raviart_thomas_element(ReferenceCellType::Tetrahedron, 1, false);
+        let bdm = create_brezzi_douglas_marini_element(ReferenceCellType::Tetrahedron, 1, false);
+        let operator = compute_interpolation_operator(&rt, &bdm).unwrap();
+        let coefficients = [1.0, -0.5, 0.25, 2.0];
+        let coefficients_bdm = apply(&operator, rt.dim(), &coefficients);
+        let point = [0.1, 0.2, 0.3];
+        for (a, b) in evaluate(&bdm, &coefficients_bdm, &point)
+            .iter()
+            .zip(evaluate(&rt, &coefficients, &point))
+        {
+            assert_relative_eq!(*a, b, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_blocked_interpolation() {
+        // A vector-valued degree 1 Lagrange element, with the DOFs at each vertex ordered by
+        // component
+        let pdim = polyset_dim(ReferenceCellType::Triangle, 1);
+        let mut coeffs = vec![0.0; 6 * 2 * pdim];
+        for k in 0..pdim {
+            for v in 0..2 {
+                coeffs[((k * 2 + v) * 2 + v) * pdim + k] = 1.0;
+            }
+        }
+        let vertices = vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.0, 1.0]];
+        let vector_p1 = CiarletElement::create(
+            ElementFamily::Lagrange,
+            ReferenceCellType::Triangle,
+            1,
+            vec![2],
+            &coeffs,
+            [vertices, vec![vec![], vec![], vec![]], vec![vec![]], vec![]],
+            [
+                vec![vec![1.0, 0.0, 0.0, 1.0]; 3],
+                vec![vec![], vec![], vec![]],
+                vec![vec![]],
+                vec![],
+            ],
+            MapType::Identity,
+            false,
+            1,
+        );
+        let p1 = create_lagrange_element(ReferenceCellType::Triangle, 1, false);
+
+        for operator in [
+            compute_interpolation_operator(&p1, &vector_p1).unwrap(),
+            compute_interpolation_operator(&vector_p1, &p1).unwrap(),
+        ] {
+            assert_eq!(operator.len(), 36);
+            for i in 0..6 {
+                for j in 0..6 {
+                    assert_relative_eq!(
+                        operator[i * 6 + j],
+                        if i == j { 1.0 } else { 0.0 },
+                        epsilon = 1e-12
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_blocked_into_piola_interpolation() {
+        // The functions (1 + x, y - 1/2) and (1 - y, 1/2 + x) are in the degree 1 Raviart-Thomas
+        // and Nédélec spaces, and in the space of vector-valued degree 1 Lagrange functions. The
+        // coefficients of the blocked Lagrange element are the values at the vertices.
+        let p1 = create_lagrange_element(ReferenceCellType::Triangle, 1, false);
+        let vertices = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+        for (e, f) in [
+            (
+                create_raviart_thomas_element(ReferenceCellType::Triangle, 1, false),
+                (|x: &[f64]| [1.0 + x[0], x[1] - 0.5]) as fn(&[f64]) -> [f64; 2],
+            ),
+            (
+                create_nedelec_element(ReferenceCellType::Triangle, 1, false),
+                |x: &[f64]| [1.0 - x[1], 0.5 + x[0]],
+            ),
+        ] {
+            let operator = compute_interpolation_operator(&p1, &e).unwrap();
+            assert_eq!(operator.len(), e.dim() * 6);
+            let coefficients = vertices.iter().flat_map(|v| f(v)).collect::<Vec<_>>();
+            let coefficients_to = apply(&operator, 6, &coefficients);
+            for point in [[0.2, 0.3], [0.7, 0.1]] {
+                for (a, b) in evaluate(&e, &coefficients_to, &point).iter().zip(f(&point)) {
+                    assert_relative_eq!(*a, b, epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_incompatible_elements() {
+        let p1 = create_lagrange_element(ReferenceCellType::Triangle, 1, false);
+        let n1 = create_nedelec_element(ReferenceCellType::Triangle, 1, false);
+        let rt = create_raviart_thomas_element(ReferenceCellType::Triangle, 1, false);
+        let regge = create_regge_element(ReferenceCellType::Triangle, 0, false);
+        let p1_tet = create_lagrange_element(ReferenceCellType::Tetrahedron, 1, false);
+        assert!(compute_interpolation_operator(&p1, &p1_tet).is_err());
+        assert!(compute_interpolation_operator(&n1, &p1).is_err());
+        assert!(compute_interpolation_operator(&n1, &rt).is_err());
+        assert!(compute_interpolation_operator(&rt, &regge).is_err());
+    }
+}
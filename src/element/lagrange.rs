@@ -1,53 +1,285 @@
 //! Lagrange elements
 
 use crate::element::*;
+use crate::polyset::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// Lagrange element
+///
+/// This is the continuous Lagrange element of the given degree created by
+/// [create_lagrange_element]. The basis of the element for each cell type and degree is computed
+/// once and shared by every `LagrangeElement` with that cell type and degree.
 pub struct LagrangeElement {
     pub celltype: ReferenceCellType,
     pub degree: usize,
 }
 
-impl FiniteElement for LagrangeElement {
-    const VALUE_SIZE: usize = 1;
-    const MAP_TYPE: MapType = MapType::Identity;
+impl LagrangeElement {
+    /// Create a continuous Lagrange element of the given degree
+    pub fn new(celltype: ReferenceCellType, degree: usize) -> Self {
+        Self { celltype, degree }
+    }
+
+    /// The cell type of the element
+    pub fn celltype(&self) -> ReferenceCellType {
+        self.celltype
+    }
+
+    /// The degree of the element
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// The Ciarlet element with the cell type and degree of this element
+    fn element(&self) -> Arc<CiarletElement> {
+        type Cache = Mutex<HashMap<(ReferenceCellType, usize), Arc<CiarletElement>>>;
+        static ELEMENTS: OnceLock<Cache> = OnceLock::new();
+        ELEMENTS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap()
+            .entry((self.celltype, self.degree))
+            .or_insert_with(|| Arc::new(create_lagrange_element(self.celltype, self.degree, false)))
+            .clone()
+    }
+}
+
+/// The points at which the DOFs of a Lagrange element associated with the interior of a sub-entity
+/// are defined
+fn entity_points(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    entity_dim: usize,
+    entity_number: usize,
+) -> Vec<f64> {
+    let cell = create_cell(cell_type);
+    let tdim = cell.dim();
+    if degree == 0 {
+        if entity_dim != tdim {
+            return vec![];
+        }
+        let nvertices = cell.vertex_count();
+        let mut midpoint = vec![0.0; tdim];
+        for v in 0..nvertices {
+            for (i, m) in midpoint.iter_mut().enumerate() {
+                *m += cell.vertices()[v * tdim + i] / nvertices as f64;
+            }
+        }
+        return midpoint;
+    }
+    if entity_dim == 0 {
+        return cell.vertices()[entity_number * tdim..(entity_number + 1) * tdim].to_vec();
+    }
+
+    let entity_vertices = cell.connectivity(entity_dim, entity_number, 0).unwrap();
+    let origin = &cell.vertices()[entity_vertices[0] * tdim..(entity_vertices[0] + 1) * tdim];
+    let axis = |v: usize| -> Vec<f64> {
+        (0..tdim)
+            .map(|i| cell.vertices()[entity_vertices[v] * tdim + i] - origin[i])
+            .collect()
+    };
+    let axes = match (entity_dim, entity_vertices.len()) {
+        (1, 2) => vec![axis(1)],
+        (2, 3) | (2, 4) => vec![axis(1), axis(2)],
+        (3, 4) | (3, 6) => vec![axis(1), axis(2), axis(3)],
+        (3, 5) => vec![axis(1), axis(2), axis(4)],
+        (3, 8) => vec![axis(1), axis(2), axis(4)],
+        _ => panic!("Unsupported sub-entity"),
+    };
+
+    let k = degree;
+    let mut lattice = vec![];
+    match (entity_dim, entity_vertices.len()) {
+        (1, 2) => {
+            for i in 1..k {
+                lattice.push([i, 0, 0]);
+            }
+        }
+        (2, 3) => {
+            for j in 1..k {
+                for i in 1..k - j {
+                    lattice.push([i, j, 0]);
+                }
+            }
+        }
+        (2, 4) => {
+            for j in 1..k {
+                for i in 1..k {
+                    lattice.push([i, j, 0]);
+                }
+            }
+        }
+        (3, 4) => {
+            for l in 1..k {
+                for j in 1..k - l {
+                    for i in 1..k - l - j {
+                        lattice.push([i, j, l]);
+                    }
+                }
+            }
+        }
+        (3, 5) => {
+            for l in 1..k {
+                for j in 1..k - l {
+                    for i in 1..k - l {
+                        lattice.push([i, j, l]);
+                    }
+                }
+            }
+        }
+        (3, 6) => {
+            for l in 1..k {
+                for j in 1..k {
+                    for i in 1..k - j {
+                        lattice.push([i, j, l]);
+                    }
+                }
+            }
+        }
+        (3, 8) => {
+            for l in 1..k {
+                for j in 1..k {
+                    for i in 1..k {
+                        lattice.push([i, j, l]);
+                    }
+                }
+            }
+        }
+        _ => panic!("Unsupported sub-entity"),
+    }
 
+    let mut points = vec![];
+    for index in lattice {
+        for i in 0..tdim {
+            let mut value = origin[i];
+            for (a, axis) in axes.iter().enumerate() {
+                value += index[a] as f64 / k as f64 * axis[i];
+            }
+            points.push(value);
+        }
+    }
+    points
+}
+
+/// The n by n identity matrix, which is the interpolation matrix of an element whose DOFs are
+/// point evaluations at the interpolation points
+fn identity(n: usize) -> Vec<f64> {
+    let mut matrix = vec![0.0; n * n];
+    for i in 0..n {
+        matrix[i * n + i] = 1.0;
+    }
+    matrix
+}
+
+impl FiniteElement for LagrangeElement {
     fn cell_type(&self) -> ReferenceCellType {
         self.celltype
     }
+    fn value_size(&self) -> usize {
+        self.element().value_size()
+    }
+    fn map_type(&self) -> MapType {
+        self.element().map_type()
+    }
     fn degree(&self) -> usize {
         self.degree
     }
     fn highest_degree(&self) -> usize {
-        self.degree
+        self.element().highest_degree()
     }
     fn family(&self) -> ElementFamily {
-        ElementFamily::Lagrange
+        self.element().family()
     }
     fn discontinuous(&self) -> bool {
-        false
+        self.element().discontinuous()
     }
     fn dim(&self) -> usize {
-        unimplemented!("dim not yet implemented for this element");
+        self.element().dim()
     }
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>) {
-        unimplemented!("tabulate not yet implemented for this element");
+    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData) {
+        self.element().tabulate(points, nderivs, data)
     }
     fn entity_dofs(&self, entity_dim: usize, entity_number: usize) -> Vec<usize> {
-        unimplemented!("entity_dofs not yet implemented for this element");
+        self.element().entity_dofs(entity_dim, entity_number)
+    }
+    fn entity_transformation(
+        &self,
+        entity_dim: usize,
+        entity_number: usize,
+        transformation: usize,
+    ) -> Vec<f64> {
+        self.element()
+            .entity_transformation(entity_dim, entity_number, transformation)
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        self.element().interpolation_points()
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        self.element().interpolation_matrix()
+    }
+}
+
+/// Create a Lagrange element of the given degree as a [CiarletElement]
+///
+/// The DOFs are point evaluations at equally spaced points. The DOFs are numbered by
+/// sub-entity: the DOFs associated with vertices come first, followed by those associated with
+/// edges, then faces, then the interior of the cell.
+pub fn create_lagrange_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    let cell = create_cell(cell_type);
+    let tdim = cell.dim();
+    let pdim = polyset_dim(cell_type, degree);
+    let mut wcoeffs = vec![0.0; pdim * pdim];
+    for i in 0..pdim {
+        wcoeffs[i * pdim + i] = 1.0;
+    }
+
+    let mut x = [vec![], vec![], vec![], vec![]];
+    let mut m = [vec![], vec![], vec![], vec![]];
+    for dim in 0..tdim + 1 {
+        for entity in 0..cell.entity_count(dim).unwrap() {
+            let points = entity_points(cell_type, degree, dim, entity);
+            let npts = points.len() / tdim;
+            let mut weights = vec![0.0; npts * npts];
+            for i in 0..npts {
+                weights[i * npts + i] = 1.0;
+            }
+            x[dim].push(points);
+            m[dim].push(weights);
+        }
     }
+
+    CiarletElement::create(
+        ElementFamily::Lagrange,
+        cell_type,
+        degree,
+        vec![],
+        &wcoeffs,
+        x,
+        m,
+        MapType::Identity,
+        discontinuous,
+        degree,
+    )
 }
 
 /// Degree 0 Lagrange element on an interval
 pub struct LagrangeElementIntervalDegree0 {}
 
 impl FiniteElement for LagrangeElementIntervalDegree0 {
-    const VALUE_SIZE: usize = 1;
-    const MAP_TYPE: MapType = MapType::Identity;
-
     fn cell_type(&self) -> ReferenceCellType {
         ReferenceCellType::Interval
     }
+    fn value_size(&self) -> usize {
+        1
+    }
+    fn map_type(&self) -> MapType {
+        MapType::Identity
+    }
     fn degree(&self) -> usize {
         0
     }
@@ -63,7 +295,7 @@ impl FiniteElement for LagrangeElementIntervalDegree0 {
     fn dim(&self) -> usize {
         1
     }
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>) {
+    fn tabulate(&self, _points: &[f64], _nderivs: usize, data: &mut TabulatedData) {
         // Basis functions are 1-x-y, x, y
         for deriv in 0..data.deriv_count() {
             for pt in 0..data.point_count() {
@@ -82,18 +314,36 @@ impl FiniteElement for LagrangeElementIntervalDegree0 {
             vec![]
         }
     }
+    fn entity_transformation(
+        &self,
+        _entity_dim: usize,
+        _entity_number: usize,
+        _transformation: usize,
+    ) -> Vec<f64> {
+        // No DOFs are associated with edges or faces
+        vec![]
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        vec![0.5]
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        identity(self.dim())
+    }
 }
 
 /// Degree 1 Lagrange element on an interval
 pub struct LagrangeElementIntervalDegree1 {}
 
 impl FiniteElement for LagrangeElementIntervalDegree1 {
-    const VALUE_SIZE: usize = 1;
-    const MAP_TYPE: MapType = MapType::Identity;
-
     fn cell_type(&self) -> ReferenceCellType {
         ReferenceCellType::Interval
     }
+    fn value_size(&self) -> usize {
+        1
+    }
+    fn map_type(&self) -> MapType {
+        MapType::Identity
+    }
     fn degree(&self) -> usize {
         1
     }
@@ -109,13 +359,13 @@ impl FiniteElement for LagrangeElementIntervalDegree1 {
     fn dim(&self) -> usize {
         2
     }
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>) {
+    fn tabulate(&self, points: &[f64], _nderivs: usize, data: &mut TabulatedData) {
         // Basis functions are 1-x-y, x, y
         for deriv in 0..data.deriv_count() {
-            for pt in 0..data.point_count() {
+            for (pt, x) in points.iter().enumerate().take(data.point_count()) {
                 if deriv == 0 {
-                    *data.get_mut(deriv, pt, 0, 0) = 1.0 - points[pt];
-                    *data.get_mut(deriv, pt, 1, 0) = points[pt];
+                    *data.get_mut(deriv, pt, 0, 0) = 1.0 - x;
+                    *data.get_mut(deriv, pt, 1, 0) = *x;
                 } else if deriv == 1 {
                     *data.get_mut(deriv, pt, 0, 0) = -1.0;
                     *data.get_mut(deriv, pt, 1, 0) = 1.0;
@@ -134,18 +384,36 @@ impl FiniteElement for LagrangeElementIntervalDegree1 {
             vec![]
         }
     }
+    fn entity_transformation(
+        &self,
+        _entity_dim: usize,
+        _entity_number: usize,
+        _transformation: usize,
+    ) -> Vec<f64> {
+        // No DOFs are associated with edges or faces
+        vec![]
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        vec![0.0, 1.0]
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        identity(self.dim())
+    }
 }
 
 /// Degree 0 Lagrange element on a triangle
 pub struct LagrangeElementTriangleDegree0 {}
 
 impl FiniteElement for LagrangeElementTriangleDegree0 {
-    const VALUE_SIZE: usize = 1;
-    const MAP_TYPE: MapType = MapType::Identity;
-
     fn cell_type(&self) -> ReferenceCellType {
         ReferenceCellType::Triangle
     }
+    fn value_size(&self) -> usize {
+        1
+    }
+    fn map_type(&self) -> MapType {
+        MapType::Identity
+    }
     fn degree(&self) -> usize {
         0
     }
@@ -161,7 +429,7 @@ impl FiniteElement for LagrangeElementTriangleDegree0 {
     fn dim(&self) -> usize {
         1
     }
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>) {
+    fn tabulate(&self, _points: &[f64], _nderivs: usize, data: &mut TabulatedData) {
         // Basis functions are 1-x-y, x, y
         for deriv in 0..data.deriv_count() {
             for pt in 0..data.point_count() {
@@ -180,18 +448,36 @@ impl FiniteElement for LagrangeElementTriangleDegree0 {
             vec![]
         }
     }
+    fn entity_transformation(
+        &self,
+        _entity_dim: usize,
+        _entity_number: usize,
+        _transformation: usize,
+    ) -> Vec<f64> {
+        // No DOFs are associated with edges or faces
+        vec![]
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        vec![1.0 / 3.0, 1.0 / 3.0]
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        identity(self.dim())
+    }
 }
 
 /// Degree 1 Lagrange element on a triangle
 pub struct LagrangeElementTriangleDegree1 {}
 
 impl FiniteElement for LagrangeElementTriangleDegree1 {
-    const VALUE_SIZE: usize = 1;
-    const MAP_TYPE: MapType = MapType::Identity;
-
     fn cell_type(&self) -> ReferenceCellType {
         ReferenceCellType::Triangle
     }
+    fn value_size(&self) -> usize {
+        1
+    }
+    fn map_type(&self) -> MapType {
+        MapType::Identity
+    }
     fn degree(&self) -> usize {
         1
     }
@@ -207,7 +493,7 @@ impl FiniteElement for LagrangeElementTriangleDegree1 {
     fn dim(&self) -> usize {
         3
     }
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>) {
+    fn tabulate(&self, points: &[f64], _nderivs: usize, data: &mut TabulatedData) {
         // Basis functions are 1-x-y, x, y
         for deriv in 0..data.deriv_count() {
             for pt in 0..data.point_count() {
@@ -238,18 +524,36 @@ impl FiniteElement for LagrangeElementTriangleDegree1 {
             vec![]
         }
     }
+    fn entity_transformation(
+        &self,
+        _entity_dim: usize,
+        _entity_number: usize,
+        _transformation: usize,
+    ) -> Vec<f64> {
+        // No DOFs are associated with edges or faces
+        vec![]
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0]
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        identity(self.dim())
+    }
 }
 
 /// Degree 0 Lagrange element on a quadrilateral
 pub struct LagrangeElementQuadrilateralDegree0 {}
 
 impl FiniteElement for LagrangeElementQuadrilateralDegree0 {
-    const VALUE_SIZE: usize = 1;
-    const MAP_TYPE: MapType = MapType::Identity;
-
     fn cell_type(&self) -> ReferenceCellType {
         ReferenceCellType::Quadrilateral
     }
+    fn value_size(&self) -> usize {
+        1
+    }
+    fn map_type(&self) -> MapType {
+        MapType::Identity
+    }
     fn degree(&self) -> usize {
         0
     }
@@ -265,7 +569,7 @@ impl FiniteElement for LagrangeElementQuadrilateralDegree0 {
     fn dim(&self) -> usize {
         1
     }
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>) {
+    fn tabulate(&self, _points: &[f64], _nderivs: usize, data: &mut TabulatedData) {
         // Basis functions are (1-x)(1-y), x(1-y), (1-x)y, xy
         for deriv in 0..data.deriv_count() {
             for pt in 0..data.point_count() {
@@ -284,18 +588,36 @@ impl FiniteElement for LagrangeElementQuadrilateralDegree0 {
             vec![]
         }
     }
+    fn entity_transformation(
+        &self,
+        _entity_dim: usize,
+        _entity_number: usize,
+        _transformation: usize,
+    ) -> Vec<f64> {
+        // No DOFs are associated with edges or faces
+        vec![]
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        vec![0.5, 0.5]
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        identity(self.dim())
+    }
 }
 
 /// Degree 1 Lagrange element on a quadrilateral
 pub struct LagrangeElementQuadrilateralDegree1 {}
 
 impl FiniteElement for LagrangeElementQuadrilateralDegree1 {
-    const VALUE_SIZE: usize = 1;
-    const MAP_TYPE: MapType = MapType::Identity;
-
     fn cell_type(&self) -> ReferenceCellType {
         ReferenceCellType::Quadrilateral
     }
+    fn value_size(&self) -> usize {
+        1
+    }
+    fn map_type(&self) -> MapType {
+        MapType::Identity
+    }
     fn degree(&self) -> usize {
         1
     }
@@ -311,7 +633,7 @@ impl FiniteElement for LagrangeElementQuadrilateralDegree1 {
     fn dim(&self) -> usize {
         4
     }
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>) {
+    fn tabulate(&self, points: &[f64], _nderivs: usize, data: &mut TabulatedData) {
         // Basis functions are (1-x)(1-y), x(1-y), (1-x)y, xy
         for deriv in 0..data.deriv_count() {
             for pt in 0..data.point_count() {
@@ -354,12 +676,28 @@ impl FiniteElement for LagrangeElementQuadrilateralDegree1 {
             vec![]
         }
     }
+    fn entity_transformation(
+        &self,
+        _entity_dim: usize,
+        _entity_number: usize,
+        _transformation: usize,
+    ) -> Vec<f64> {
+        // No DOFs are associated with edges or faces
+        vec![]
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0]
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        identity(self.dim())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::element::*;
     use approx::*;
+    use std::sync::Arc;
 
     fn check_dofs(e: impl FiniteElement) {
         let cell_dim = match e.cell_type() {
@@ -389,6 +727,184 @@ mod test {
         assert_eq!(ndofs, e.dim());
     }
 
+    const CELL_TYPES: [ReferenceCellType; 7] = [
+        ReferenceCellType::Interval,
+        ReferenceCellType::Triangle,
+        ReferenceCellType::Quadrilateral,
+        ReferenceCellType::Tetrahedron,
+        ReferenceCellType::Hexahedron,
+        ReferenceCellType::Prism,
+        ReferenceCellType::Pyramid,
+    ];
+
+    /// A point in the interior of each reference cell
+    fn interior_point(celltype: ReferenceCellType) -> Vec<f64> {
+        match celltype {
+            ReferenceCellType::Interval => vec![0.3],
+            ReferenceCellType::Triangle => vec![0.3, 0.2],
+            ReferenceCellType::Quadrilateral => vec![0.3, 0.6],
+            ReferenceCellType::Tetrahedron => vec![0.3, 0.2, 0.1],
+            ReferenceCellType::Hexahedron => vec![0.3, 0.6, 0.8],
+            ReferenceCellType::Prism => vec![0.3, 0.2, 0.7],
+            ReferenceCellType::Pyramid => vec![0.3, 0.2, 0.4],
+        }
+    }
+
+    #[test]
+    fn test_lagrange_dofs() {
+        for celltype in CELL_TYPES {
+            for degree in 0..5 {
+                check_dofs(LagrangeElement::new(celltype, degree));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_dof_points() {
+        for celltype in CELL_TYPES {
+            for degree in 0..5 {
+                let e = LagrangeElement::new(celltype, degree);
+                let points = e.interpolation_points();
+                let mut data = TabulatedData::new(&e, 0, e.dim());
+                e.tabulate(&points, 0, &mut data);
+                for pt in 0..e.dim() {
+                    for fun in 0..e.dim() {
+                        assert_relative_eq!(
+                            *data.get(0, pt, fun, 0),
+                            if pt == fun { 1.0 } else { 0.0 },
+                            epsilon = 1e-10
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_derivatives() {
+        let h = 1e-6;
+        for celltype in CELL_TYPES {
+            for degree in 1..4 {
+                let e = LagrangeElement::new(celltype, degree);
+                let point = interior_point(celltype);
+                let tdim = point.len();
+
+                let mut points = point.clone();
+                for d in 0..tdim {
+                    let mut p = point.clone();
+                    p[d] += h;
+                    points.extend(p);
+                }
+                let mut data = TabulatedData::new(&e, 1, tdim + 1);
+                e.tabulate(&points, 1, &mut data);
+
+                for fun in 0..e.dim() {
+                    for d in 0..tdim {
+                        assert_relative_eq!(
+                            *data.get(d + 1, 0, fun, 0),
+                            (*data.get(0, d + 1, fun, 0) - *data.get(0, 0, fun, 0)) / h,
+                            epsilon = 1e-4
+                        );
+                    }
+                }
+                // The basis functions form a partition of unity
+                for d in 0..tdim + 1 {
+                    let sum: f64 = (0..e.dim()).map(|fun| *data.get(d, 0, fun, 0)).sum();
+                    assert_relative_eq!(sum, if d == 0 { 1.0 } else { 0.0 }, epsilon = 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_fields() {
+        let e = LagrangeElement {
+            celltype: ReferenceCellType::Quadrilateral,
+            degree: 3,
+        };
+        assert_eq!(e.celltype(), ReferenceCellType::Quadrilateral);
+        assert_eq!(e.degree(), 3);
+        assert_eq!(e.cell_type(), e.celltype);
+        assert_eq!(e.dim(), 16);
+
+        // Elements with the same cell type and degree share their basis
+        let e1 = LagrangeElement::new(ReferenceCellType::Quadrilateral, 3);
+        assert!(Arc::ptr_eq(&e.element(), &e1.element()));
+        let e2 = LagrangeElement::new(ReferenceCellType::Quadrilateral, 2);
+        assert_eq!(e2.dim(), 9);
+    }
+
+    #[test]
+    fn test_lagrange_1_triangle_arbitrary_degree() {
+        let e = LagrangeElement::new(ReferenceCellType::Triangle, 1);
+        assert_eq!(e.dim(), 3);
+        let mut data = TabulatedData::new(&e, 1, 6);
+        let points = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.0, 0.0, 0.5, 0.5, 0.5];
+        e.tabulate(&points, 1, &mut data);
+
+        let e1 = LagrangeElementTriangleDegree1 {};
+        let mut data1 = TabulatedData::new(&e1, 1, 6);
+        e1.tabulate(&points, 1, &mut data1);
+
+        for deriv in 0..3 {
+            for pt in 0..6 {
+                for fun in 0..3 {
+                    assert_relative_eq!(
+                        *data.get(deriv, pt, fun, 0),
+                        *data1.get(deriv, pt, fun, 0),
+                        epsilon = 1e-12
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_1_quadrilateral_arbitrary_degree() {
+        let e = LagrangeElement::new(ReferenceCellType::Quadrilateral, 1);
+        assert_eq!(e.dim(), 4);
+        let mut data = TabulatedData::new(&e, 1, 6);
+        let points = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.25, 0.5, 0.3, 0.2];
+        e.tabulate(&points, 1, &mut data);
+
+        let e1 = LagrangeElementQuadrilateralDegree1 {};
+        let mut data1 = TabulatedData::new(&e1, 1, 6);
+        e1.tabulate(&points, 1, &mut data1);
+
+        for deriv in 0..3 {
+            for pt in 0..6 {
+                for fun in 0..4 {
+                    assert_relative_eq!(
+                        *data.get(deriv, pt, fun, 0),
+                        *data1.get(deriv, pt, fun, 0),
+                        epsilon = 1e-12
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_2_interval() {
+        let e = LagrangeElement::new(ReferenceCellType::Interval, 2);
+        let mut data = TabulatedData::new(&e, 2, 4);
+        let points = vec![0.0, 0.2, 0.4, 1.0];
+        e.tabulate(&points, 2, &mut data);
+
+        for (pt, x) in points.iter().enumerate() {
+            assert_relative_eq!(
+                *data.get(0, pt, 0, 0),
+                (1.0 - x) * (1.0 - 2.0 * x),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(*data.get(0, pt, 1, 0), x * (2.0 * x - 1.0), epsilon = 1e-12);
+            assert_relative_eq!(*data.get(0, pt, 2, 0), 4.0 * x * (1.0 - x), epsilon = 1e-12);
+            assert_relative_eq!(*data.get(2, pt, 0, 0), 4.0, epsilon = 1e-10);
+            assert_relative_eq!(*data.get(2, pt, 1, 0), 4.0, epsilon = 1e-10);
+            assert_relative_eq!(*data.get(2, pt, 2, 0), -8.0, epsilon = 1e-10);
+        }
+    }
+
     #[test]
     fn test_lagrange_0_interval() {
         let e = LagrangeElementIntervalDegree0 {};
@@ -411,9 +927,9 @@ mod test {
         let points = vec![0.0, 0.2, 0.4, 1.0];
         e.tabulate(&points, 0, &mut data);
 
-        for pt in 0..4 {
-            assert_relative_eq!(*data.get(0, pt, 0, 0), 1.0 - points[pt]);
-            assert_relative_eq!(*data.get(0, pt, 1, 0), points[pt]);
+        for (pt, x) in points.iter().enumerate() {
+            assert_relative_eq!(*data.get(0, pt, 0, 0), 1.0 - x);
+            assert_relative_eq!(*data.get(0, pt, 1, 0), *x);
         }
         check_dofs(e);
     }
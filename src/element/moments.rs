@@ -0,0 +1,280 @@
+//! DOF functionals defined as integral moments against polynomials on sub-entities
+
+use crate::cell::*;
+use crate::element::*;
+use crate::polyset::*;
+use crate::quadrature::*;
+
+/// The type of a sub-entity of a reference cell
+pub(crate) fn sub_entity_type(
+    cell_type: ReferenceCellType,
+    entity_dim: usize,
+    entity_number: usize,
+) -> ReferenceCellType {
+    let cell = create_cell(cell_type);
+    if entity_dim == cell.dim() {
+        return cell_type;
+    }
+    match entity_dim {
+        1 => ReferenceCellType::Interval,
+        2 => match cell.connectivity(2, entity_number, 0).unwrap().len() {
+            3 => ReferenceCellType::Triangle,
+            4 => ReferenceCellType::Quadrilateral,
+            _ => panic!("Unsupported face"),
+        },
+        _ => panic!("Unsupported sub-entity"),
+    }
+}
+
+/// The origin and axes of a sub-entity of a reference cell
+///
+/// A point X on the reference sub-entity is mapped to origin + sum_i X_i axes_i.
+pub(crate) fn sub_entity_geometry(
+    cell_type: ReferenceCellType,
+    entity_dim: usize,
+    entity_number: usize,
+) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let cell = create_cell(cell_type);
+    let tdim = cell.dim();
+    if entity_dim == tdim {
+        let axes = (0..tdim)
+            .map(|i| (0..tdim).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+        return (vec![0.0; tdim], axes);
+    }
+    let vertices = cell.connectivity(entity_dim, entity_number, 0).unwrap();
+    let origin = cell.vertices()[vertices[0] * tdim..(vertices[0] + 1) * tdim].to_vec();
+    let axes = (1..entity_dim + 1)
+        .map(|v| {
+            (0..tdim)
+                .map(|i| cell.vertices()[vertices[v] * tdim + i] - origin[i])
+                .collect()
+        })
+        .collect();
+    (origin, axes)
+}
+
+/// Map points on a reference sub-entity to the cell, using the origin and axes of the sub-entity
+pub(crate) fn map_points(origin: &[f64], axes: &[Vec<f64>], points: &[f64]) -> Vec<f64> {
+    let tdim = origin.len();
+    let entity_dim = axes.len();
+    let npts = points.len() / entity_dim;
+    let mut mapped = vec![0.0; npts * tdim];
+    for p in 0..npts {
+        for i in 0..tdim {
+            mapped[p * tdim + i] = origin[i]
+                + axes
+                    .iter()
+                    .enumerate()
+                    .map(|(j, a)| points[p * entity_dim + j] * a[i])
+                    .sum::<f64>();
+        }
+    }
+    mapped
+}
+
+/// Interpolation points or weights with no DOFs associated with any sub-entity of a cell
+pub(crate) fn empty_dofs(cell_type: ReferenceCellType) -> [Vec<Vec<f64>>; 4] {
+    let cell = create_cell(cell_type);
+    let mut dofs = [vec![], vec![], vec![], vec![]];
+    for (d, dofs_d) in dofs.iter_mut().enumerate().take(cell.dim() + 1) {
+        *dofs_d = vec![vec![]; cell.entity_count(d).unwrap()];
+    }
+    dofs
+}
+
+/// The (unnormalised) normal to a facet of a reference cell
+pub(crate) fn facet_normal(cell_type: ReferenceCellType, facet: usize) -> Vec<f64> {
+    let tdim = create_cell(cell_type).dim();
+    let (_, axes) = sub_entity_geometry(cell_type, tdim - 1, facet);
+    match tdim {
+        2 => vec![-axes[0][1], axes[0][0]],
+        3 => vec![
+            axes[0][1] * axes[1][2] - axes[0][2] * axes[1][1],
+            axes[0][2] * axes[1][0] - axes[0][0] * axes[1][2],
+            axes[0][0] * axes[1][1] - axes[0][1] * axes[1][0],
+        ],
+        _ => panic!("Unsupported cell"),
+    }
+}
+
+/// Create DOFs defined by integral moments against the orthonormal polynomials of degree
+/// `degree` on each sub-entity of dimension `entity_dim`
+///
+/// Each orthonormal polynomial is paired with each of the vectors returned by `directions`,
+/// which is passed the axes of the sub-entity; the DOF is then the integral of the dot product
+/// of the function with the direction multiplied by the polynomial. The DOFs for each polynomial
+/// are numbered consecutively. The integrals are computed using a quadrature rule of degree
+/// `degree + q_degree`.
+///
+/// The points and weights are returned in the format used by [crate::element::CiarletElement::create].
+pub(crate) fn make_integral_moments(
+    cell_type: ReferenceCellType,
+    entity_dim: usize,
+    degree: usize,
+    q_degree: usize,
+    value_size: usize,
+    directions: impl Fn(usize, &[Vec<f64>]) -> Vec<Vec<f64>>,
+) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let cell = create_cell(cell_type);
+    let mut all_points = vec![];
+    let mut all_weights = vec![];
+    for e in 0..cell.entity_count(entity_dim).unwrap() {
+        let sub_type = sub_entity_type(cell_type, entity_dim, e);
+        let (origin, axes) = sub_entity_geometry(cell_type, entity_dim, e);
+        let dirs = directions(e, &axes);
+        let rule = make_quadrature(sub_type, degree + q_degree);
+        let pdim = polyset_dim(sub_type, degree);
+        let mut table = vec![0.0; rule.npoints * pdim];
+        tabulate_legendre_polynomials(sub_type, &rule.points, degree, 0, &mut table);
+
+        let points = map_points(&origin, &axes, &rule.points);
+
+        let ndofs = pdim * dirs.len();
+        let mut weights = vec![0.0; ndofs * value_size * rule.npoints];
+        for k in 0..pdim {
+            for (d, dir) in dirs.iter().enumerate() {
+                let dof = k * dirs.len() + d;
+                for (v, dv) in dir.iter().enumerate() {
+                    for p in 0..rule.npoints {
+                        weights[(dof * value_size + v) * rule.npoints + p] =
+                            rule.weights[p] * table[p * pdim + k] * dv;
+                    }
+                }
+            }
+        }
+        all_points.push(points);
+        all_weights.push(weights);
+    }
+    (all_points, all_weights)
+}
+
+/// Create DOFs defined by integral moments of the normal component on each facet
+pub(crate) fn make_normal_integral_moments(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    q_degree: usize,
+) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let tdim = create_cell(cell_type).dim();
+    make_integral_moments(cell_type, tdim - 1, degree, q_degree, tdim, |f, _| {
+        vec![facet_normal(cell_type, f)]
+    })
+}
+
+/// Create DOFs defined by integral moments of the tangential component on each edge
+pub(crate) fn make_tangent_integral_moments(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    q_degree: usize,
+) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let tdim = create_cell(cell_type).dim();
+    make_integral_moments(cell_type, 1, degree, q_degree, tdim, |_, axes| {
+        vec![axes[0].clone()]
+    })
+}
+
+/// Create DOFs defined by integral moments of the components tangential to each sub-entity of
+/// dimension `entity_dim`
+///
+/// On the interior of the cell, this gives moments of every component of the function.
+pub(crate) fn make_vector_integral_moments(
+    cell_type: ReferenceCellType,
+    entity_dim: usize,
+    degree: usize,
+    q_degree: usize,
+) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let tdim = create_cell(cell_type).dim();
+    make_integral_moments(cell_type, entity_dim, degree, q_degree, tdim, |_, axes| {
+        axes.to_vec()
+    })
+}
+
+/// Create DOFs defined by integral moments against the basis functions of a vector-valued
+/// element on each sub-entity of dimension `entity_dim`
+///
+/// The basis functions of `moment_element` are mapped onto each sub-entity using its axes, and
+/// the DOF is the integral of the dot product of the function with the mapped basis function.
+/// The integrals are computed using a quadrature rule of degree `moment_element.highest_degree() + q_degree`.
+pub(crate) fn make_dot_integral_moments(
+    cell_type: ReferenceCellType,
+    entity_dim: usize,
+    moment_element: &CiarletElement,
+    q_degree: usize,
+) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let cell = create_cell(cell_type);
+    let tdim = cell.dim();
+    assert_eq!(moment_element.value_size(), entity_dim);
+    let mut all_points = vec![];
+    let mut all_weights = vec![];
+    for e in 0..cell.entity_count(entity_dim).unwrap() {
+        assert_eq!(
+            sub_entity_type(cell_type, entity_dim, e),
+            moment_element.cell_type()
+        );
+        let (origin, axes) = sub_entity_geometry(cell_type, entity_dim, e);
+        let rule = make_quadrature(
+            moment_element.cell_type(),
+            moment_element.highest_degree() + q_degree,
+        );
+        let mut table = TabulatedData::new(moment_element, 0, rule.npoints);
+        moment_element.tabulate(&rule.points, 0, &mut table);
+
+        let points = map_points(&origin, &axes, &rule.points);
+
+        let ndofs = moment_element.dim();
+        let mut weights = vec![0.0; ndofs * tdim * rule.npoints];
+        for dof in 0..ndofs {
+            for v in 0..tdim {
+                for p in 0..rule.npoints {
+                    weights[(dof * tdim + v) * rule.npoints + p] = rule.weights[p]
+                        * axes
+                            .iter()
+                            .enumerate()
+                            .map(|(j, a)| table.get(0, p, dof, j) * a[v])
+                            .sum::<f64>();
+                }
+            }
+        }
+        all_points.push(points);
+        all_weights.push(weights);
+    }
+    (all_points, all_weights)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::element::moments::*;
+    use approx::*;
+
+    #[test]
+    fn test_facet_normals() {
+        assert_eq!(
+            facet_normal(ReferenceCellType::Triangle, 0),
+            vec![-1.0, -1.0]
+        );
+        assert_eq!(
+            facet_normal(ReferenceCellType::Triangle, 1),
+            vec![-1.0, 0.0]
+        );
+        assert_eq!(facet_normal(ReferenceCellType::Triangle, 2), vec![0.0, 1.0]);
+        assert_eq!(
+            facet_normal(ReferenceCellType::Tetrahedron, 0),
+            vec![1.0, 1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_integral_moments() {
+        // The weights of moments against constants on each edge sum to the length of the
+        // reference interval
+        let (points, weights) =
+            make_integral_moments(ReferenceCellType::Triangle, 1, 0, 2, 1, |_, _| {
+                vec![vec![1.0]]
+            });
+        assert_eq!(points.len(), 3);
+        for (p, w) in points.iter().zip(weights.iter()) {
+            assert_eq!(p.len(), 2 * w.len());
+            assert_relative_eq!(w.iter().sum::<f64>(), 1.0, epsilon = 1e-14);
+        }
+    }
+}
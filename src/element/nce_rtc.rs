@@ -0,0 +1,282 @@
+//! Raviart-Thomas and Nédélec elements on quadrilaterals and hexahedra
+
+use crate::element::moments::*;
+use crate::element::*;
+use crate::polyset::*;
+
+/// Coefficients of a vector-valued tensor product polynomial space on a quadrilateral or
+/// hexahedron
+///
+/// Component `v` of the space contains the products of polynomials of degree at most
+/// `max_degree(v, a)` in the direction of axis `a`.
+fn tensor_product_span(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    max_degree: impl Fn(usize, usize) -> usize,
+) -> Vec<f64> {
+    let tdim = create_cell(cell_type).dim();
+    let pdim = polyset_dim(cell_type, degree);
+    let mut wcoeffs = vec![];
+    for v in 0..tdim {
+        for k in 0..pdim {
+            // The orthonormal polynomial k has degree k / (n+1)^(tdim-1-a) % (n+1) in axis a
+            let in_span = (0..tdim).all(|a| {
+                k / (degree + 1).pow((tdim - 1 - a) as u32) % (degree + 1) <= max_degree(v, a)
+            });
+            if in_span {
+                let mut row = vec![0.0; tdim * pdim];
+                row[v * pdim + k] = 1.0;
+                wcoeffs.extend(row);
+            }
+        }
+    }
+    wcoeffs
+}
+
+/// Create an H(div) Raviart-Thomas element of the given degree on a quadrilateral (RTCF) or
+/// hexahedron (NCF)
+///
+/// Component i of the polynomial space has degree at most k in x_i and k-1 in the other
+/// variables. The DOFs are integral moments of the normal component against degree k-1
+/// polynomials on each facet, and integral moments against degree k-1 H(curl) elements on the
+/// interior.
+pub fn create_rtc_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    if cell_type != ReferenceCellType::Quadrilateral && cell_type != ReferenceCellType::Hexahedron {
+        unimplemented!(
+            "RTCF and NCF elements are only implemented on quadrilaterals and hexahedra"
+        );
+    }
+    assert!(degree >= 1);
+    let tdim = create_cell(cell_type).dim();
+    let wcoeffs = tensor_product_span(
+        cell_type,
+        degree,
+        |v, a| {
+            if v == a {
+                degree
+            } else {
+                degree - 1
+            }
+        },
+    );
+
+    let mut x = empty_dofs(cell_type);
+    let mut m = empty_dofs(cell_type);
+    (x[tdim - 1], m[tdim - 1]) = make_normal_integral_moments(cell_type, degree - 1, degree);
+    if degree > 1 {
+        let interior_element = create_nce_element(cell_type, degree - 1, true);
+        (x[tdim], m[tdim]) = make_dot_integral_moments(cell_type, tdim, &interior_element, degree);
+    }
+
+    CiarletElement::create(
+        ElementFamily::RaviartThomas,
+        cell_type,
+        degree,
+        vec![tdim],
+        &wcoeffs,
+        x,
+        m,
+        MapType::ContravariantPiola,
+        discontinuous,
+        degree,
+    )
+}
+
+/// Create an H(curl) Nédélec element of the given degree on a quadrilateral (RTCE) or
+/// hexahedron (NCE)
+///
+/// Component i of the polynomial space has degree at most k-1 in x_i and k in the other
+/// variables. The DOFs are integral moments of the tangential component against degree k-1
+/// polynomials on each edge, integral moments against degree k-1 H(div) elements on each face of
+/// a hexahedron, and integral moments against degree k-1 H(div) elements on the interior.
+pub fn create_nce_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    if cell_type != ReferenceCellType::Quadrilateral && cell_type != ReferenceCellType::Hexahedron {
+        unimplemented!(
+            "RTCE and NCE elements are only implemented on quadrilaterals and hexahedra"
+        );
+    }
+    assert!(degree >= 1);
+    let tdim = create_cell(cell_type).dim();
+    let wcoeffs = tensor_product_span(
+        cell_type,
+        degree,
+        |v, a| {
+            if v == a {
+                degree - 1
+            } else {
+                degree
+            }
+        },
+    );
+
+    let mut x = empty_dofs(cell_type);
+    let mut m = empty_dofs(cell_type);
+    (x[1], m[1]) = make_tangent_integral_moments(cell_type, degree - 1, degree);
+    if degree > 1 {
+        if tdim == 3 {
+            let face_element =
+                create_rtc_element(ReferenceCellType::Quadrilateral, degree - 1, true);
+            (x[2], m[2]) = make_dot_integral_moments(cell_type, 2, &face_element, degree);
+        }
+        let interior_element = create_rtc_element(cell_type, degree - 1, true);
+        (x[tdim], m[tdim]) = make_dot_integral_moments(cell_type, tdim, &interior_element, degree);
+    }
+
+    CiarletElement::create(
+        ElementFamily::NedelecFirstKind,
+        cell_type,
+        degree,
+        vec![tdim],
+        &wcoeffs,
+        x,
+        m,
+        MapType::CovariantPiola,
+        discontinuous,
+        degree,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::element::moments::*;
+    use crate::element::*;
+    use approx::*;
+
+    fn check_dofs(e: &impl FiniteElement) {
+        let cell = create_cell(e.cell_type());
+        let mut ndofs = 0;
+        for dim in 0..cell.dim() + 1 {
+            for entity in 0..cell.entity_count(dim).unwrap() {
+                ndofs += e.entity_dofs(dim, entity).len();
+            }
+        }
+        assert_eq!(ndofs, e.dim());
+    }
+
+    #[test]
+    fn test_rtc_1_quadrilateral() {
+        let e = create_rtc_element(ReferenceCellType::Quadrilateral, 1, false);
+        assert_eq!(e.dim(), 4);
+        assert_eq!(e.value_size(), 2);
+
+        // The basis functions are (0, 1 - y), (x - 1, 0), (-x, 0) and (0, y)
+        let points = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.2, 0.3, 0.9];
+        let mut data = TabulatedData::new(&e, 0, 5);
+        e.tabulate(&points, 0, &mut data);
+        for pt in 0..5 {
+            let (x, y) = (points[2 * pt], points[2 * pt + 1]);
+            let expected = [[0.0, 1.0 - y], [x - 1.0, 0.0], [-x, 0.0], [0.0, y]];
+            for (fun, value) in expected.iter().enumerate() {
+                for (v, value_v) in value.iter().enumerate() {
+                    assert_relative_eq!(*data.get(0, pt, fun, v), value_v, epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_nce_rtc_dims() {
+        for degree in 1..4 {
+            let e = create_rtc_element(ReferenceCellType::Quadrilateral, degree, false);
+            assert_eq!(e.dim(), 2 * degree * (degree + 1));
+            assert_eq!(e.map_type(), MapType::ContravariantPiola);
+            check_dofs(&e);
+
+            let e = create_nce_element(ReferenceCellType::Quadrilateral, degree, false);
+            assert_eq!(e.dim(), 2 * degree * (degree + 1));
+            assert_eq!(e.map_type(), MapType::CovariantPiola);
+            check_dofs(&e);
+
+            let e = create_rtc_element(ReferenceCellType::Hexahedron, degree, false);
+            assert_eq!(e.dim(), 3 * degree * degree * (degree + 1));
+            for face in 0..6 {
+                assert_eq!(e.entity_dofs(2, face).len(), degree * degree);
+            }
+            check_dofs(&e);
+
+            let e = create_nce_element(ReferenceCellType::Hexahedron, degree, false);
+            assert_eq!(e.dim(), 3 * degree * (degree + 1) * (degree + 1));
+            for edge in 0..12 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree);
+            }
+            check_dofs(&e);
+        }
+    }
+
+    #[test]
+    fn test_rtc_normal_continuity() {
+        for cell_type in [
+            ReferenceCellType::Quadrilateral,
+            ReferenceCellType::Hexahedron,
+        ] {
+            let cell = create_cell(cell_type);
+            let tdim = cell.dim();
+            let nfacets = cell.entity_count(tdim - 1).unwrap();
+            let mut points = vec![];
+            for f in 0..nfacets {
+                let (origin, axes) = sub_entity_geometry(cell_type, tdim - 1, f);
+                points.extend(map_points(&origin, &axes, &[0.3, 0.6][..tdim - 1]));
+            }
+            for degree in 1..4 {
+                let e = create_rtc_element(cell_type, degree, false);
+                let mut data = TabulatedData::new(&e, 0, nfacets);
+                e.tabulate(&points, 0, &mut data);
+                for f in 0..nfacets {
+                    let n = facet_normal(cell_type, f);
+                    let dofs = e.entity_dofs(tdim - 1, f);
+                    for fun in 0..e.dim() {
+                        if !dofs.contains(&fun) {
+                            let normal = (0..tdim)
+                                .map(|v| data.get(0, f, fun, v) * n[v])
+                                .sum::<f64>();
+                            assert_relative_eq!(normal, 0.0, epsilon = 1e-10);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_nce_tangential_continuity() {
+        for cell_type in [
+            ReferenceCellType::Quadrilateral,
+            ReferenceCellType::Hexahedron,
+        ] {
+            let cell = create_cell(cell_type);
+            let tdim = cell.dim();
+            let nedges = cell.entity_count(1).unwrap();
+            let mut points = vec![];
+            let mut tangents = vec![];
+            for edge in 0..nedges {
+                let (origin, axes) = sub_entity_geometry(cell_type, 1, edge);
+                points.extend(map_points(&origin, &axes, &[0.3]));
+                tangents.push(axes[0].clone());
+            }
+            for degree in 1..4 {
+                let e = create_nce_element(cell_type, degree, false);
+                let mut data = TabulatedData::new(&e, 0, nedges);
+                e.tabulate(&points, 0, &mut data);
+                for (edge, t) in tangents.iter().enumerate() {
+                    let dofs = e.entity_dofs(1, edge);
+                    for fun in 0..e.dim() {
+                        if !dofs.contains(&fun) {
+                            let tangential = (0..tdim)
+                                .map(|v| data.get(0, edge, fun, v) * t[v])
+                                .sum::<f64>();
+                            assert_relative_eq!(tangential, 0.0, epsilon = 1e-10);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
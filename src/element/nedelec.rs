@@ -0,0 +1,338 @@
+//! Nédélec elements
+
+use crate::element::moments::*;
+use crate::element::*;
+use crate::linalg::orthonormal_row_basis;
+use crate::polyset::*;
+use crate::quadrature::*;
+
+/// Create a Nédélec (first kind) element of the given degree on a triangle or tetrahedron
+///
+/// The polynomial space is P_{k-1}^d + S_k, where S_k is the space of homogeneous degree k
+/// vector polynomials p with p.x = 0. The DOFs are integral moments of the tangential component
+/// against degree k-1 polynomials on each edge, integral moments of the tangential components
+/// against vector degree k-2 polynomials on each face of a tetrahedron, and integral moments
+/// against vector polynomials of degree k-2 (triangle) or k-3 (tetrahedron) on the interior.
+pub fn create_nedelec_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    if cell_type != ReferenceCellType::Triangle && cell_type != ReferenceCellType::Tetrahedron {
+        unimplemented!("Nédélec elements are only implemented on triangles and tetrahedra");
+    }
+    assert!(degree >= 1);
+    let tdim = create_cell(cell_type).dim();
+    let pdim = polyset_dim(cell_type, degree);
+    let nlow = polyset_dim(cell_type, degree - 1);
+    let nlower = if degree >= 2 {
+        polyset_dim(cell_type, degree - 2)
+    } else {
+        0
+    };
+
+    let ncoeffs = tdim * pdim;
+    let mut wcoeffs = vec![0.0; tdim * nlow * ncoeffs];
+    for v in 0..tdim {
+        for i in 0..nlow {
+            wcoeffs[(v * nlow + i) * ncoeffs + v * pdim + i] = 1.0;
+        }
+    }
+
+    // Add x cross the degree k-1 polynomials that are orthogonal to P_{k-2}. In 3D, these
+    // functions are not linearly independent, so the dependent functions are removed below
+    let rule = make_quadrature(cell_type, 2 * degree);
+    let mut table = vec![0.0; rule.npoints * pdim];
+    tabulate_legendre_polynomials(cell_type, &rule.points, degree, 0, &mut table);
+    let ncross = if tdim == 2 { 1 } else { 3 };
+    for q in nlower..nlow {
+        for c in 0..ncross {
+            let mut row = vec![0.0; ncoeffs];
+            for p in 0..rule.npoints {
+                let x = &rule.points[p * tdim..(p + 1) * tdim];
+                let value = match (tdim, c) {
+                    (2, _) => [-x[1], x[0], 0.0],
+                    (_, 0) => [0.0, -x[2], x[1]],
+                    (_, 1) => [x[2], 0.0, -x[0]],
+                    _ => [-x[1], x[0], 0.0],
+                };
+                for v in 0..tdim {
+                    for k in 0..pdim {
+                        row[v * pdim + k] +=
+                            rule.weights[p] * value[v] * table[p * pdim + q] * table[p * pdim + k];
+                    }
+                }
+            }
+            wcoeffs.extend(row);
+        }
+    }
+    let wcoeffs = orthonormal_row_basis(&wcoeffs, ncoeffs);
+
+    let mut x = empty_dofs(cell_type);
+    let mut m = empty_dofs(cell_type);
+    (x[1], m[1]) = make_tangent_integral_moments(cell_type, degree - 1, degree);
+    if tdim == 3 && degree > 1 {
+        (x[2], m[2]) = make_vector_integral_moments(cell_type, 2, degree - 2, degree);
+    }
+    if degree > tdim - 1 {
+        (x[tdim], m[tdim]) = make_vector_integral_moments(cell_type, tdim, degree - tdim, degree);
+    }
+
+    CiarletElement::create(
+        ElementFamily::NedelecFirstKind,
+        cell_type,
+        degree,
+        vec![tdim],
+        &wcoeffs,
+        x,
+        m,
+        MapType::CovariantPiola,
+        discontinuous,
+        degree,
+    )
+}
+
+/// Create a Nédélec second kind element of the given degree on a triangle or tetrahedron
+///
+/// The polynomial space is P_k^d. The DOFs are integral moments of the tangential component
+/// against degree k polynomials on each edge, integral moments against degree k-1 Raviart-Thomas
+/// elements on each face of a tetrahedron, and integral moments against Raviart-Thomas elements
+/// of degree k-1 (triangle) or k-2 (tetrahedron) on the interior.
+pub fn create_nedelec_second_kind_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    if cell_type != ReferenceCellType::Triangle && cell_type != ReferenceCellType::Tetrahedron {
+        unimplemented!("Nédélec elements are only implemented on triangles and tetrahedra");
+    }
+    assert!(degree >= 1);
+    let tdim = create_cell(cell_type).dim();
+    let pdim = polyset_dim(cell_type, degree);
+    let mut wcoeffs = vec![0.0; tdim * pdim * tdim * pdim];
+    for i in 0..tdim * pdim {
+        wcoeffs[i * tdim * pdim + i] = 1.0;
+    }
+
+    let mut x = empty_dofs(cell_type);
+    let mut m = empty_dofs(cell_type);
+    (x[1], m[1]) = make_tangent_integral_moments(cell_type, degree, degree);
+    if tdim == 3 && degree > 1 {
+        let face_element =
+            create_raviart_thomas_element(ReferenceCellType::Triangle, degree - 1, true);
+        (x[2], m[2]) = make_dot_integral_moments(cell_type, 2, &face_element, degree);
+    }
+    if degree > tdim - 1 {
+        let interior_element = create_raviart_thomas_element(cell_type, degree + 1 - tdim, true);
+        (x[tdim], m[tdim]) = make_dot_integral_moments(cell_type, tdim, &interior_element, degree);
+    }
+
+    CiarletElement::create(
+        ElementFamily::NedelecSecondKind,
+        cell_type,
+        degree,
+        vec![tdim],
+        &wcoeffs,
+        x,
+        m,
+        MapType::CovariantPiola,
+        discontinuous,
+        degree,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::element::*;
+    use approx::*;
+
+    fn check_dofs(e: &impl FiniteElement) {
+        let cell = create_cell(e.cell_type());
+        let mut ndofs = 0;
+        for dim in 0..cell.dim() + 1 {
+            for entity in 0..cell.entity_count(dim).unwrap() {
+                ndofs += e.entity_dofs(dim, entity).len();
+            }
+        }
+        assert_eq!(ndofs, e.dim());
+    }
+
+    #[test]
+    fn test_nedelec_1_triangle() {
+        let e = create_nedelec_element(ReferenceCellType::Triangle, 1, false);
+        assert_eq!(e.dim(), 3);
+        assert_eq!(e.value_size(), 2);
+        assert_eq!(e.map_type(), MapType::CovariantPiola);
+        assert_eq!(e.family(), ElementFamily::NedelecFirstKind);
+
+        // The basis functions are (-y, x), (y, 1 - x) and (1 - y, x)
+        let points = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.0, 0.0, 0.5, 0.3, 0.2];
+        let mut data = TabulatedData::new(&e, 0, 6);
+        e.tabulate(&points, 0, &mut data);
+        for pt in 0..6 {
+            let (x, y) = (points[2 * pt], points[2 * pt + 1]);
+            assert_relative_eq!(*data.get(0, pt, 0, 0), -y, epsilon = 1e-12);
+            assert_relative_eq!(*data.get(0, pt, 0, 1), x, epsilon = 1e-12);
+            assert_relative_eq!(*data.get(0, pt, 1, 0), y, epsilon = 1e-12);
+            assert_relative_eq!(*data.get(0, pt, 1, 1), 1.0 - x, epsilon = 1e-12);
+            assert_relative_eq!(*data.get(0, pt, 2, 0), 1.0 - y, epsilon = 1e-12);
+            assert_relative_eq!(*data.get(0, pt, 2, 1), x, epsilon = 1e-12);
+        }
+        check_dofs(&e);
+    }
+
+    #[test]
+    fn test_nedelec_dims() {
+        for degree in 1..5 {
+            let e = create_nedelec_element(ReferenceCellType::Triangle, degree, false);
+            assert_eq!(e.dim(), degree * (degree + 2));
+            for edge in 0..3 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree);
+            }
+            assert_eq!(e.entity_dofs(2, 0).len(), degree * (degree - 1));
+            check_dofs(&e);
+
+            let e = create_nedelec_element(ReferenceCellType::Tetrahedron, degree, false);
+            assert_eq!(e.dim(), degree * (degree + 2) * (degree + 3) / 2);
+            assert_eq!(e.value_size(), 3);
+            for edge in 0..6 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree);
+            }
+            for face in 0..4 {
+                assert_eq!(e.entity_dofs(2, face).len(), degree * (degree - 1));
+            }
+            check_dofs(&e);
+        }
+    }
+
+    #[test]
+    fn test_nedelec_tangential_continuity() {
+        // The tangential component of a basis function on an edge is zero unless the function
+        // is associated with that edge
+        for (cell_type, tdim) in [
+            (ReferenceCellType::Triangle, 2),
+            (ReferenceCellType::Tetrahedron, 3),
+        ] {
+            let cell = create_cell(cell_type);
+            for degree in 1..4 {
+                let e = create_nedelec_element(cell_type, degree, false);
+                let nedges = cell.entity_count(1).unwrap();
+                let mut points = vec![];
+                let mut tangents = vec![];
+                for edge in 0..nedges {
+                    let v = cell.connectivity(1, edge, 0).unwrap();
+                    let v0 = &cell.vertices()[v[0] * tdim..(v[0] + 1) * tdim];
+                    let v1 = &cell.vertices()[v[1] * tdim..(v[1] + 1) * tdim];
+                    for i in 0..tdim {
+                        points.push(0.7 * v0[i] + 0.3 * v1[i]);
+                        tangents.push(v1[i] - v0[i]);
+                    }
+                }
+                let mut data = TabulatedData::new(&e, 0, nedges);
+                e.tabulate(&points, 0, &mut data);
+                for edge in 0..nedges {
+                    let dofs = e.entity_dofs(1, edge);
+                    for fun in 0..e.dim() {
+                        if !dofs.contains(&fun) {
+                            let tangential = (0..tdim)
+                                .map(|v| data.get(0, edge, fun, v) * tangents[edge * tdim + v])
+                                .sum::<f64>();
+                            assert_relative_eq!(tangential, 0.0, epsilon = 1e-10);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_nedelec_derivatives() {
+        let e = create_nedelec_element(ReferenceCellType::Tetrahedron, 2, false);
+        let h = 1e-6;
+        let points = vec![
+            0.2,
+            0.3,
+            0.1,
+            0.2 + h,
+            0.3,
+            0.1,
+            0.2,
+            0.3 + h,
+            0.1,
+            0.2,
+            0.3,
+            0.1 + h,
+        ];
+        let mut data = TabulatedData::new(&e, 1, 4);
+        e.tabulate(&points, 1, &mut data);
+        for fun in 0..e.dim() {
+            for v in 0..3 {
+                for d in 0..3 {
+                    assert_relative_eq!(
+                        *data.get(d + 1, 0, fun, v),
+                        (data.get(0, d + 1, fun, v) - data.get(0, 0, fun, v)) / h,
+                        epsilon = 1e-4
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_nedelec_second_kind_dims() {
+        for degree in 1..5 {
+            let e = create_nedelec_second_kind_element(ReferenceCellType::Triangle, degree, false);
+            assert_eq!(e.dim(), (degree + 1) * (degree + 2));
+            assert_eq!(e.map_type(), MapType::CovariantPiola);
+            assert_eq!(e.family(), ElementFamily::NedelecSecondKind);
+            for edge in 0..3 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree + 1);
+            }
+            check_dofs(&e);
+
+            let e =
+                create_nedelec_second_kind_element(ReferenceCellType::Tetrahedron, degree, false);
+            assert_eq!(e.dim(), (degree + 1) * (degree + 2) * (degree + 3) / 2);
+            for edge in 0..6 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree + 1);
+            }
+            for face in 0..4 {
+                assert_eq!(e.entity_dofs(2, face).len(), (degree - 1) * (degree + 1));
+            }
+            check_dofs(&e);
+        }
+    }
+
+    #[test]
+    fn test_nedelec_second_kind_tangential_continuity() {
+        let cell = create_cell(ReferenceCellType::Tetrahedron);
+        for degree in 1..4 {
+            let e =
+                create_nedelec_second_kind_element(ReferenceCellType::Tetrahedron, degree, false);
+            let mut points = vec![];
+            let mut tangents = vec![];
+            for edge in 0..6 {
+                let v = cell.connectivity(1, edge, 0).unwrap();
+                let v0 = &cell.vertices()[v[0] * 3..(v[0] + 1) * 3];
+                let v1 = &cell.vertices()[v[1] * 3..(v[1] + 1) * 3];
+                for i in 0..3 {
+                    points.push(0.6 * v0[i] + 0.4 * v1[i]);
+                    tangents.push(v1[i] - v0[i]);
+                }
+            }
+            let mut data = TabulatedData::new(&e, 0, 6);
+            e.tabulate(&points, 0, &mut data);
+            for edge in 0..6 {
+                let dofs = e.entity_dofs(1, edge);
+                for fun in 0..e.dim() {
+                    if !dofs.contains(&fun) {
+                        let tangential = (0..3)
+                            .map(|v| data.get(0, edge, fun, v) * tangents[edge * 3 + v])
+                            .sum::<f64>();
+                        assert_relative_eq!(tangential, 0.0, epsilon = 1e-10);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,17 +1,95 @@
-//! Lagrange elements
+//! Raviart-Thomas elements
 
+use crate::element::moments::*;
 use crate::element::*;
+use crate::polyset::*;
+use crate::quadrature::*;
+
+/// Create a Raviart-Thomas element of the given degree on a triangle or tetrahedron
+///
+/// The polynomial space is P_{k-1}^d + x P_{k-1}. The DOFs are integral moments of the normal
+/// component against degree k-1 polynomials on each facet, and integral moments against vector
+/// degree k-2 polynomials on the interior.
+pub fn create_raviart_thomas_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    if cell_type != ReferenceCellType::Triangle && cell_type != ReferenceCellType::Tetrahedron {
+        unimplemented!("Raviart-Thomas elements are only implemented on triangles and tetrahedra");
+    }
+    assert!(degree >= 1);
+    let tdim = create_cell(cell_type).dim();
+    let pdim = polyset_dim(cell_type, degree);
+    let nlow = polyset_dim(cell_type, degree - 1);
+    let nlower = if degree >= 2 {
+        polyset_dim(cell_type, degree - 2)
+    } else {
+        0
+    };
+
+    let ncoeffs = tdim * pdim;
+    let mut wcoeffs = vec![0.0; (tdim * nlow + nlow - nlower) * ncoeffs];
+    for v in 0..tdim {
+        for i in 0..nlow {
+            wcoeffs[(v * nlow + i) * ncoeffs + v * pdim + i] = 1.0;
+        }
+    }
+
+    // Add x times the degree k-1 polynomials that are orthogonal to P_{k-2}
+    let rule = make_quadrature(cell_type, 2 * degree);
+    let mut table = vec![0.0; rule.npoints * pdim];
+    tabulate_legendre_polynomials(cell_type, &rule.points, degree, 0, &mut table);
+    for (i, q) in (nlower..nlow).enumerate() {
+        let row = tdim * nlow + i;
+        for v in 0..tdim {
+            for k in 0..pdim {
+                wcoeffs[row * ncoeffs + v * pdim + k] = (0..rule.npoints)
+                    .map(|p| {
+                        rule.weights[p]
+                            * rule.points[p * tdim + v]
+                            * table[p * pdim + q]
+                            * table[p * pdim + k]
+                    })
+                    .sum();
+            }
+        }
+    }
+
+    let mut x = empty_dofs(cell_type);
+    let mut m = empty_dofs(cell_type);
+    (x[tdim - 1], m[tdim - 1]) = make_normal_integral_moments(cell_type, degree - 1, degree);
+    if degree > 1 {
+        (x[tdim], m[tdim]) = make_vector_integral_moments(cell_type, tdim, degree - 2, degree);
+    }
+
+    CiarletElement::create(
+        ElementFamily::RaviartThomas,
+        cell_type,
+        degree,
+        vec![tdim],
+        &wcoeffs,
+        x,
+        m,
+        MapType::ContravariantPiola,
+        discontinuous,
+        degree,
+    )
+}
 
 /// Degree 1 Raviart-Thomas element on a triangle
 pub struct RaviartThomasElementTriangleDegree1 {}
 
 impl FiniteElement for RaviartThomasElementTriangleDegree1 {
-    const VALUE_SIZE: usize = 2;
-    const MAP_TYPE: MapType = MapType::ContravariantPiola;
-
     fn cell_type(&self) -> ReferenceCellType {
         ReferenceCellType::Triangle
     }
+    fn value_size(&self) -> usize {
+        2
+    }
+    fn map_type(&self) -> MapType {
+        MapType::ContravariantPiola
+    }
     fn degree(&self) -> usize {
         1
     }
@@ -27,7 +105,7 @@ impl FiniteElement for RaviartThomasElementTriangleDegree1 {
     fn dim(&self) -> usize {
         3
     }
-    fn tabulate(&self, points: &[f64], nderivs: usize, data: &mut TabulatedData<Self>) {
+    fn tabulate(&self, points: &[f64], _nderivs: usize, data: &mut TabulatedData) {
         // Basis functions are 1-x-y, x, y
         for deriv in 0..data.deriv_count() {
             for pt in 0..data.point_count() {
@@ -67,6 +145,33 @@ impl FiniteElement for RaviartThomasElementTriangleDegree1 {
             vec![]
         }
     }
+    fn entity_transformation(
+        &self,
+        entity_dim: usize,
+        _entity_number: usize,
+        _transformation: usize,
+    ) -> Vec<f64> {
+        // Reversing an edge reverses its normal
+        if entity_dim == 1 {
+            vec![-1.0]
+        } else {
+            vec![]
+        }
+    }
+    fn interpolation_points(&self) -> Vec<f64> {
+        vec![0.5, 0.5, 0.0, 0.5, 0.5, 0.0]
+    }
+    fn interpolation_matrix(&self) -> Vec<f64> {
+        // The DOFs are the integrals of the normal components along the edges, computed using
+        // the midpoint rule, with the normals scaled by the lengths of the edges. Entry (i, 3v+p)
+        // is the weight of DOF i for component v at point p
+        let mut matrix = vec![0.0; 18];
+        matrix[0] = -1.0;
+        matrix[3] = -1.0;
+        matrix[7] = -1.0;
+        matrix[17] = 1.0;
+        matrix
+    }
 }
 
 #[cfg(test)]
@@ -120,4 +225,134 @@ mod test {
         }
         check_dofs(e);
     }
+
+    #[test]
+    fn test_raviart_thomas_1_triangle_arbitrary_degree() {
+        let e = create_raviart_thomas_element(ReferenceCellType::Triangle, 1, false);
+        let e1 = RaviartThomasElementTriangleDegree1 {};
+        let points = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.0, 0.0, 0.5, 0.3, 0.2];
+        let mut data = TabulatedData::new(&e, 1, 6);
+        e.tabulate(&points, 1, &mut data);
+        let mut data1 = TabulatedData::new(&e1, 1, 6);
+        e1.tabulate(&points, 1, &mut data1);
+        for deriv in 0..3 {
+            for pt in 0..6 {
+                for fun in 0..3 {
+                    for v in 0..2 {
+                        assert_relative_eq!(
+                            *data.get(deriv, pt, fun, v),
+                            *data1.get(deriv, pt, fun, v),
+                            epsilon = 1e-12
+                        );
+                    }
+                }
+            }
+        }
+        for edge in 0..3 {
+            assert_eq!(e.entity_dofs(1, edge), e1.entity_dofs(1, edge));
+        }
+    }
+
+    #[test]
+    fn test_raviart_thomas_dims() {
+        for degree in 1..5 {
+            let e = create_raviart_thomas_element(ReferenceCellType::Triangle, degree, false);
+            assert_eq!(e.dim(), degree * (degree + 2));
+            assert_eq!(e.value_size(), 2);
+            assert_eq!(e.map_type(), MapType::ContravariantPiola);
+            assert_eq!(e.family(), ElementFamily::RaviartThomas);
+            for edge in 0..3 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree);
+            }
+            check_dofs(e);
+
+            let e = create_raviart_thomas_element(ReferenceCellType::Tetrahedron, degree, false);
+            assert_eq!(e.dim(), degree * (degree + 1) * (degree + 3) / 2);
+            assert_eq!(e.value_size(), 3);
+            for face in 0..4 {
+                assert_eq!(e.entity_dofs(2, face).len(), degree * (degree + 1) / 2);
+            }
+            check_dofs(e);
+        }
+    }
+
+    #[test]
+    fn test_raviart_thomas_normal_continuity() {
+        // The normal component of a basis function on a facet is zero unless the function is
+        // associated with that facet
+        let tet_points = [
+            [0.2, 0.3, 0.5],
+            [0.0, 0.3, 0.1],
+            [0.3, 0.0, 0.6],
+            [0.2, 0.5, 0.0],
+        ];
+        let tet_normals = [
+            [1.0, 1.0, 1.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ];
+        let tri_points = [[0.7, 0.3], [0.0, 0.4], [0.3, 0.0]];
+        let tri_normals = [[1.0, 1.0], [1.0, 0.0], [0.0, 1.0]];
+        for degree in 1..4 {
+            let e = create_raviart_thomas_element(ReferenceCellType::Triangle, degree, false);
+            let mut data = TabulatedData::new(&e, 0, 3);
+            e.tabulate(&tri_points.concat(), 0, &mut data);
+            for (f, n) in tri_normals.iter().enumerate() {
+                let dofs = e.entity_dofs(1, f);
+                for fun in 0..e.dim() {
+                    if !dofs.contains(&fun) {
+                        let normal = (0..2).map(|v| data.get(0, f, fun, v) * n[v]).sum::<f64>();
+                        assert_relative_eq!(normal, 0.0, epsilon = 1e-10);
+                    }
+                }
+            }
+
+            let e = create_raviart_thomas_element(ReferenceCellType::Tetrahedron, degree, false);
+            let mut data = TabulatedData::new(&e, 0, 4);
+            e.tabulate(&tet_points.concat(), 0, &mut data);
+            for (f, n) in tet_normals.iter().enumerate() {
+                let dofs = e.entity_dofs(2, f);
+                for fun in 0..e.dim() {
+                    if !dofs.contains(&fun) {
+                        let normal = (0..3).map(|v| data.get(0, f, fun, v) * n[v]).sum::<f64>();
+                        assert_relative_eq!(normal, 0.0, epsilon = 1e-10);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_raviart_thomas_derivatives() {
+        let e = create_raviart_thomas_element(ReferenceCellType::Tetrahedron, 2, false);
+        let h = 1e-6;
+        let points = vec![
+            0.2,
+            0.3,
+            0.1,
+            0.2 + h,
+            0.3,
+            0.1,
+            0.2,
+            0.3 + h,
+            0.1,
+            0.2,
+            0.3,
+            0.1 + h,
+        ];
+        let mut data = TabulatedData::new(&e, 1, 4);
+        e.tabulate(&points, 1, &mut data);
+        for fun in 0..e.dim() {
+            for v in 0..3 {
+                for d in 0..3 {
+                    assert_relative_eq!(
+                        *data.get(d + 1, 0, fun, v),
+                        (data.get(0, d + 1, fun, v) - data.get(0, 0, fun, v)) / h,
+                        epsilon = 1e-4
+                    );
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,177 @@
+//! Regge elements
+
+use crate::element::moments::*;
+use crate::element::*;
+use crate::polyset::*;
+
+/// Coefficients of a basis of the space of symmetric tdim by tdim matrices whose entries are
+/// polynomials of the given degree
+pub(crate) fn symmetric_matrix_span(cell_type: ReferenceCellType, degree: usize) -> Vec<f64> {
+    let tdim = create_cell(cell_type).dim();
+    let pdim = polyset_dim(cell_type, degree);
+    let ncoeffs = tdim * tdim * pdim;
+    let mut wcoeffs = vec![];
+    for a in 0..tdim {
+        for b in a..tdim {
+            for k in 0..pdim {
+                let mut row = vec![0.0; ncoeffs];
+                if a == b {
+                    row[(a * tdim + b) * pdim + k] = 1.0;
+                } else {
+                    row[(a * tdim + b) * pdim + k] = 0.5f64.sqrt();
+                    row[(b * tdim + a) * pdim + k] = 0.5f64.sqrt();
+                }
+                wcoeffs.extend(row);
+            }
+        }
+    }
+    wcoeffs
+}
+
+/// The tangents to the edges of a sub-entity, given the axes of the sub-entity
+fn edge_tangents(axes: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let mut tangents = axes.to_vec();
+    for i in 0..axes.len() {
+        for j in i + 1..axes.len() {
+            tangents.push(axes[j].iter().zip(&axes[i]).map(|(a, b)| a - b).collect());
+        }
+    }
+    tangents
+}
+
+/// Create a Regge element of the given degree on a triangle or tetrahedron
+///
+/// The polynomial space is the space of symmetric matrices whose entries are degree k
+/// polynomials. The DOFs on each sub-entity of dimension d are integral moments of t^T S t
+/// against degree k+1-d polynomials, where t is the tangent to an edge of the sub-entity.
+pub fn create_regge_element(
+    cell_type: ReferenceCellType,
+    degree: usize,
+    discontinuous: bool,
+) -> CiarletElement {
+    if cell_type != ReferenceCellType::Triangle && cell_type != ReferenceCellType::Tetrahedron {
+        unimplemented!("Regge elements are only implemented on triangles and tetrahedra");
+    }
+    let tdim = create_cell(cell_type).dim();
+    let wcoeffs = symmetric_matrix_span(cell_type, degree);
+
+    let mut x = empty_dofs(cell_type);
+    let mut m = empty_dofs(cell_type);
+    for d in 1..tdim + 1 {
+        if degree + 1 >= d {
+            (x[d], m[d]) = make_integral_moments(
+                cell_type,
+                d,
+                degree + 1 - d,
+                degree,
+                tdim * tdim,
+                |_, axes| {
+                    edge_tangents(axes)
+                        .iter()
+                        .map(|t| {
+                            (0..tdim * tdim)
+                                .map(|v| t[v / tdim] * t[v % tdim])
+                                .collect()
+                        })
+                        .collect()
+                },
+            );
+        }
+    }
+
+    CiarletElement::create(
+        ElementFamily::Regge,
+        cell_type,
+        degree,
+        vec![tdim, tdim],
+        &wcoeffs,
+        x,
+        m,
+        MapType::DoubleCovariantPiola,
+        discontinuous,
+        degree,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::element::moments::*;
+    use crate::element::*;
+    use approx::*;
+
+    fn check_dofs(e: &impl FiniteElement) {
+        let cell = create_cell(e.cell_type());
+        let mut ndofs = 0;
+        for dim in 0..cell.dim() + 1 {
+            for entity in 0..cell.entity_count(dim).unwrap() {
+                ndofs += e.entity_dofs(dim, entity).len();
+            }
+        }
+        assert_eq!(ndofs, e.dim());
+    }
+
+    #[test]
+    fn test_regge_dims() {
+        for degree in 0..4 {
+            let e = create_regge_element(ReferenceCellType::Triangle, degree, false);
+            assert_eq!(e.dim(), 3 * (degree + 1) * (degree + 2) / 2);
+            assert_eq!(e.value_size(), 4);
+            assert_eq!(e.value_shape(), [2, 2]);
+            assert_eq!(e.map_type(), MapType::DoubleCovariantPiola);
+            for edge in 0..3 {
+                assert_eq!(e.entity_dofs(1, edge).len(), degree + 1);
+            }
+            check_dofs(&e);
+
+            let e = create_regge_element(ReferenceCellType::Tetrahedron, degree, false);
+            assert_eq!(e.dim(), (degree + 1) * (degree + 2) * (degree + 3));
+            assert_eq!(e.value_size(), 9);
+            for face in 0..4 {
+                assert_eq!(e.entity_dofs(2, face).len(), 3 * degree * (degree + 1) / 2);
+            }
+            check_dofs(&e);
+        }
+    }
+
+    #[test]
+    fn test_regge_tangential_continuity() {
+        for cell_type in [ReferenceCellType::Triangle, ReferenceCellType::Tetrahedron] {
+            let cell = create_cell(cell_type);
+            let tdim = cell.dim();
+            let nedges = cell.entity_count(1).unwrap();
+            let mut points = vec![];
+            let mut tangents = vec![];
+            for edge in 0..nedges {
+                let (origin, axes) = sub_entity_geometry(cell_type, 1, edge);
+                points.extend(map_points(&origin, &axes, &[0.3]));
+                tangents.push(axes[0].clone());
+            }
+            for degree in 0..3 {
+                let e = create_regge_element(cell_type, degree, false);
+                let mut data = TabulatedData::new(&e, 0, nedges);
+                e.tabulate(&points, 0, &mut data);
+                for (edge, t) in tangents.iter().enumerate() {
+                    let dofs = e.entity_dofs(1, edge);
+                    for fun in 0..e.dim() {
+                        // The basis functions are symmetric
+                        for a in 0..tdim {
+                            for b in 0..tdim {
+                                assert_relative_eq!(
+                                    *data.get(0, edge, fun, a * tdim + b),
+                                    *data.get(0, edge, fun, b * tdim + a),
+                                    epsilon = 1e-10
+                                );
+                            }
+                        }
+                        if !dofs.contains(&fun) {
+                            let tt = (0..tdim * tdim)
+                                .map(|v| data.get(0, edge, fun, v) * t[v / tdim] * t[v % tdim])
+                                .sum::<f64>();
+                            assert_relative_eq!(tt, 0.0, epsilon = 1e-10);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
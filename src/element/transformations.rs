@@ -0,0 +1,676 @@
+//! Transformations of the DOFs associated with sub-entities whose orientation differs from the
+//! orientation of the sub-entity on the reference cell
+
+use crate::element::moments::*;
+use crate::element::*;
+use crate::linalg::{det_and_inverse, inverse};
+use crate::map::map_matrix_entry;
+
+/// The number of base transformations of a sub-entity of the given type
+///
+/// An edge has one base transformation (reversal) and a face has two (rotation and reflection).
+pub(crate) fn transformation_count(entity_type: ReferenceCellType) -> usize {
+    match entity_type {
+        ReferenceCellType::Interval => 1,
+        ReferenceCellType::Triangle | ReferenceCellType::Quadrilateral => 2,
+        _ => 0,
+    }
+}
+
+/// The affine map s -> origin + J s of a reference sub-entity to itself for a base transformation
+///
+/// The Jacobian J is returned as a row-major matrix. The rotation of a triangle maps vertex 1 to
+/// vertex 0, vertex 2 to vertex 1, and vertex 0 to vertex 2; the rotation of a quadrilateral maps
+/// vertex 1 to vertex 0, vertex 3 to vertex 1, vertex 2 to vertex 3 and vertex 0 to vertex 2. The
+/// reflections swap vertices 1 and 2.
+fn entity_map(entity_type: ReferenceCellType, transformation: usize) -> (Vec<f64>, Vec<f64>) {
+    match (entity_type, transformation) {
+        (ReferenceCellType::Interval, 0) => (vec![1.0], vec![-1.0]),
+        (ReferenceCellType::Triangle, 0) => (vec![0.0, 1.0], vec![0.0, 1.0, -1.0, -1.0]),
+        (ReferenceCellType::Quadrilateral, 0) => (vec![0.0, 1.0], vec![0.0, 1.0, -1.0, 0.0]),
+        (ReferenceCellType::Triangle, 1) | (ReferenceCellType::Quadrilateral, 1) => {
+            (vec![0.0, 0.0], vec![0.0, 1.0, 1.0, 0.0])
+        }
+        _ => panic!("Unsupported transformation"),
+    }
+}
+
+/// Apply a base transformation of a sub-entity to points on that sub-entity
+pub(crate) fn transform_points(
+    cell_type: ReferenceCellType,
+    entity_dim: usize,
+    entity_number: usize,
+    transformation: usize,
+    points: &[f64],
+) -> Vec<f64> {
+    let tdim = create_cell(cell_type).dim();
+    let (origin, axes) = sub_entity_geometry(cell_type, entity_dim, entity_number);
+    let (map_origin, map_jacobian) = entity_map(
+        sub_entity_type(cell_type, entity_dim, entity_number),
+        transformation,
+    );
+
+    // The coordinates of a point on the sub-entity are computed using the left inverse
+    // (A^T A)^{-1} A^T of the matrix A whose columns are the axes
+    let mut ata = vec![0.0; entity_dim * entity_dim];
+    for i in 0..entity_dim {
+        for j in 0..entity_dim {
+            ata[i * entity_dim + j] = (0..tdim).map(|k| axes[i][k] * axes[j][k]).sum();
+        }
+    }
+    let ata_inv = inverse(&ata, entity_dim);
+
+    let mut entity_points = vec![];
+    for x in points.chunks(tdim) {
+        let at_x = (0..entity_dim)
+            .map(|i| {
+                (0..tdim)
+                    .map(|k| axes[i][k] * (x[k] - origin[k]))
+                    .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+        let s = (0..entity_dim)
+            .map(|i| {
+                (0..entity_dim)
+                    .map(|j| ata_inv[i * entity_dim + j] * at_x[j])
+                    .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+        for i in 0..entity_dim {
+            entity_points.push(
+                map_origin[i]
+                    + (0..entity_dim)
+                        .map(|j| map_jacobian[i * entity_dim + j] * s[j])
+                        .sum::<f64>(),
+            );
+        }
+    }
+    map_points(&origin, &axes, &entity_points)
+}
+
+/// The Jacobian of a linear map of the cell that acts as a base transformation on the tangent
+/// space of a sub-entity
+///
+/// The map acts as the identity on a complement of the tangent space spanned by coordinate
+/// directions, so its determinant is the determinant of the map of the sub-entity.
+fn cell_jacobian(
+    cell_type: ReferenceCellType,
+    entity_dim: usize,
+    entity_number: usize,
+    transformation: usize,
+) -> Vec<f64> {
+    let tdim = create_cell(cell_type).dim();
+    let (_, mut basis) = sub_entity_geometry(cell_type, entity_dim, entity_number);
+    let (_, map_jacobian) = entity_map(
+        sub_entity_type(cell_type, entity_dim, entity_number),
+        transformation,
+    );
+
+    // Extend the axes of the sub-entity to a basis B of R^tdim by adding coordinate directions
+    // that are independent of the previous vectors, checked using the Gram determinant
+    for direction in 0..tdim {
+        let mut candidate = basis.clone();
+        candidate.push(
+            (0..tdim)
+                .map(|i| if i == direction { 1.0 } else { 0.0 })
+                .collect(),
+        );
+        let m = candidate.len();
+        let gram = (0..m * m)
+            .map(|ij| {
+                (0..tdim)
+                    .map(|k| candidate[ij / m][k] * candidate[ij % m][k])
+                    .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+        let mut inv = [0.0; 9];
+        if m <= tdim && det_and_inverse(&gram, m, &mut inv).abs() > 1e-10 {
+            basis = candidate;
+        }
+    }
+    assert_eq!(basis.len(), tdim);
+
+    // J = B diag(J_entity, I) B^{-1}
+    let b = (0..tdim)
+        .flat_map(|i| basis.iter().map(move |v| v[i]))
+        .collect::<Vec<_>>();
+    let b_inv = inverse(&b, tdim);
+    let mut middle = vec![0.0; tdim * tdim];
+    for i in 0..tdim {
+        for j in 0..tdim {
+            middle[i * tdim + j] = if i < entity_dim && j < entity_dim {
+                map_jacobian[i * entity_dim + j]
+            } else if i == j {
+                1.0
+            } else {
+                0.0
+            };
+        }
+    }
+    let mut jacobian = vec![0.0; tdim * tdim];
+    for i in 0..tdim {
+        for j in 0..tdim {
+            jacobian[i * tdim + j] = (0..tdim)
+                .map(|k| {
+                    b[i * tdim + k]
+                        * (0..tdim)
+                            .map(|l| middle[k * tdim + l] * b_inv[l * tdim + j])
+                            .sum::<f64>()
+                })
+                .sum();
+        }
+    }
+    jacobian
+}
+
+/// Compute the matrix of a base transformation of the DOFs associated with a sub-entity of a
+/// Ciarlet element
+///
+/// Entry (i, j) of the matrix is the DOF functional i applied to basis function j after it has
+/// been pulled back by the map of the cell given by the base transformation. The pull back by
+/// this map is computed as the push forward by its inverse.
+pub(crate) fn compute_entity_transformation(
+    element: &CiarletElement,
+    entity_dim: usize,
+    entity_number: usize,
+    transformation: usize,
+) -> Vec<f64> {
+    let cell_type = element.cell_type();
+    let tdim = create_cell(cell_type).dim();
+    let value_size = element.value_size();
+    let dofs = element.entity_dofs(entity_dim, entity_number);
+    let ndofs = dofs.len();
+    if ndofs == 0 {
+        return vec![];
+    }
+    let points = &element.entity_interpolation_points()[entity_dim][entity_number];
+    let weights = &element.entity_interpolation_weights()[entity_dim][entity_number];
+    let npts = points.len() / tdim;
+
+    let mapped_points =
+        transform_points(cell_type, entity_dim, entity_number, transformation, points);
+    let mut table = TabulatedData::new(element, 0, npts);
+    element.tabulate(&mapped_points, 0, &mut table);
+
+    let j = cell_jacobian(cell_type, entity_dim, entity_number, transformation);
+    let mut k = vec![0.0; tdim * tdim];
+    let det = det_and_inverse(&j, tdim, &mut k);
+
+    let mut matrix = vec![0.0; ndofs * ndofs];
+    for i in 0..ndofs {
+        for (col, dof) in dofs.iter().enumerate() {
+            matrix[i * ndofs + col] = (0..npts)
+                .map(|p| {
+                    (0..value_size)
+                        .map(|v| {
+                            weights[(i * value_size + v) * npts + p]
+                                * (0..value_size)
+                                    .map(|b| {
+                                        map_matrix_entry(
+                                            element.map_type(),
+                                            &k,
+                                            &j,
+                                            1.0 / det,
+                                            tdim,
+                                            tdim,
+                                            v,
+                                            b,
+                                        ) * table.get(0, p, *dof, b)
+                                    })
+                                    .sum::<f64>()
+                        })
+                        .sum::<f64>()
+                })
+                .sum();
+        }
+    }
+    matrix
+}
+
+/// Call `f(entity_dim, entity_number, transformation)` for each base transformation that makes
+/// up the DOF transformation given by an orientation bitfield, in the order they are applied
+///
+/// For each face, the transformation is F^reflected R^rotations. If `rotate_first` is true, the
+/// rotations are listed before the reflection; otherwise the reflection is listed first. See
+/// [FiniteElement::t_apply] for the format of `cell_info`.
+fn for_each_transformation(
+    cell_type: ReferenceCellType,
+    cell_info: u32,
+    rotate_first: bool,
+    mut f: impl FnMut(usize, usize, usize),
+) {
+    let cell = create_cell(cell_type);
+    let tdim = cell.dim();
+    let face_start = if tdim == 3 { 3 * cell.face_count() } else { 0 };
+    if tdim > 1 {
+        for e in 0..cell.edge_count() {
+            if (cell_info >> (face_start + e)) & 1 == 1 {
+                f(1, e, 0);
+            }
+        }
+    }
+    if tdim == 3 {
+        for face in 0..cell.face_count() {
+            let rotations = (cell_info >> (3 * face + 1)) & 3;
+            let reflected = (cell_info >> (3 * face)) & 1 == 1;
+            if reflected && !rotate_first {
+                f(2, face, 1);
+            }
+            for _ in 0..rotations {
+                f(2, face, 0);
+            }
+            if reflected && rotate_first {
+                f(2, face, 1);
+            }
+        }
+    }
+}
+
+/// Apply the DOF transformations given by an orientation bitfield to data whose first index is
+/// the DOF number, with `n` entries for each DOF
+///
+/// If `inverse` is true, the inverse of the transformation is applied; if `transpose` is true,
+/// the transpose is applied. See [FiniteElement::t_apply] for the format of `cell_info`.
+pub(crate) fn apply_dof_transformations<F: FiniteElement + ?Sized>(
+    element: &F,
+    data: &mut [f64],
+    n: usize,
+    cell_info: u32,
+    inverse_transformation: bool,
+    transpose: bool,
+) {
+    assert_eq!(data.len(), element.dim() * n);
+    // R is applied first when applying T or T^{-T}, and F is applied first when applying T^{-1}
+    // or T^T
+    let rotate_first = inverse_transformation == transpose;
+    for_each_transformation(
+        element.cell_type(),
+        cell_info,
+        rotate_first,
+        |entity_dim, entity_number, transformation| {
+            let dofs = element.entity_dofs(entity_dim, entity_number);
+            if dofs.is_empty() {
+                return;
+            }
+            let size = dofs.len();
+            let m = element.entity_transformation(entity_dim, entity_number, transformation);
+            let m = if inverse_transformation {
+                inverse(&m, size)
+            } else {
+                m
+            };
+            let m = if transpose {
+                (0..size * size)
+                    .map(|i| m[(i % size) * size + i / size])
+                    .collect()
+            } else {
+                m
+            };
+            apply_matrix(data, n, &dofs, &m);
+        },
+    );
+}
+
+/// The permutation given by a permutation matrix: entry i is the column of the non-zero entry
+/// in row i, or None if the matrix is not a permutation matrix
+pub(crate) fn matrix_to_permutation(matrix: &[f64], size: usize) -> Option<Vec<usize>> {
+    let mut permutation = vec![];
+    for row in matrix.chunks(size) {
+        let mut column = None;
+        for (j, entry) in row.iter().enumerate() {
+            if (entry - 1.0).abs() < 1e-10 && column.is_none() {
+                column = Some(j);
+            } else if entry.abs() > 1e-10 {
+                return None;
+            }
+        }
+        permutation.push(column?);
+    }
+    Some(permutation)
+}
+
+/// Apply the DOF permutation given by an orientation bitfield to a list of DOF numbers
+///
+/// This panics if the DOF transformations of the element are not permutations. If `inverse` is
+/// true, the inverse of the permutation is applied.
+pub(crate) fn apply_dof_permutations<F: FiniteElement + ?Sized>(
+    element: &F,
+    dofs: &mut [usize],
+    cell_info: u32,
+    inverse_permutation: bool,
+) {
+    assert_eq!(dofs.len(), element.dim());
+    for_each_transformation(
+        element.cell_type(),
+        cell_info,
+        !inverse_permutation,
+        |entity_dim, entity_number, transformation| {
+            let entity_dofs = element.entity_dofs(entity_dim, entity_number);
+            if entity_dofs.is_empty() {
+                return;
+            }
+            let permutation = matrix_to_permutation(
+                &element.entity_transformation(entity_dim, entity_number, transformation),
+                entity_dofs.len(),
+            )
+            .expect("The DOF transformations of this element are not permutations");
+            let old = entity_dofs.iter().map(|d| dofs[*d]).collect::<Vec<_>>();
+            for (i, p) in permutation.iter().enumerate() {
+                if inverse_permutation {
+                    dofs[entity_dofs[*p]] = old[i];
+                } else {
+                    dofs[entity_dofs[i]] = old[*p];
+                }
+            }
+        },
+    );
+}
+
+/// Replace the rows of `data` (with `n` columns) for the given DOFs by M times these rows
+fn apply_matrix(data: &mut [f64], n: usize, dofs: &[usize], matrix: &[f64]) {
+    let size = dofs.len();
+    let mut rows = vec![0.0; size];
+    for c in 0..n {
+        for (r, dof) in rows.iter_mut().zip(dofs) {
+            *r = data[dof * n + c];
+        }
+        for (i, dof) in dofs.iter().enumerate() {
+            data[dof * n + c] = (0..size).map(|j| matrix[i * size + j] * rows[j]).sum();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cell::*;
+    use crate::element::*;
+    use crate::map::push_forward;
+    use approx::*;
+
+    /// Multiply two n by n row-major matrices
+    fn matmul(a: &[f64], b: &[f64], n: usize) -> Vec<f64> {
+        (0..n * n)
+            .map(|ij| (0..n).map(|k| a[ij / n * n + k] * b[k * n + ij % n]).sum())
+            .collect()
+    }
+
+    fn assert_identity(m: &[f64], n: usize) {
+        for i in 0..n {
+            for j in 0..n {
+                assert_relative_eq!(
+                    m[i * n + j],
+                    if i == j { 1.0 } else { 0.0 },
+                    epsilon = 1e-10
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_transformations() {
+        let e = create_lagrange_element(ReferenceCellType::Triangle, 3, false);
+        for (a, b) in e
+            .entity_transformation(1, 0, 0)
+            .iter()
+            .zip([0.0, 1.0, 1.0, 0.0])
+        {
+            assert_relative_eq!(*a, b, epsilon = 1e-12);
+        }
+
+        // The transformations of LagrangeElement agree with those of the Ciarlet element
+        let e = create_lagrange_element(ReferenceCellType::Tetrahedron, 4, false);
+        let e1 = LagrangeElement::new(ReferenceCellType::Tetrahedron, 4);
+        for (d, count) in [(1, 1), (2, 2)] {
+            for entity in 0..4 {
+                for t in 0..count {
+                    let m = e.entity_transformation(d, entity, t);
+                    for (a, b) in m.iter().zip(e1.entity_transformation(d, entity, t)) {
+                        assert_relative_eq!(*a, b, epsilon = 1e-10);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_vector_transformations() {
+        let e = create_nedelec_element(ReferenceCellType::Tetrahedron, 1, false);
+        for edge in 0..6 {
+            assert_relative_eq!(
+                e.entity_transformation(1, edge, 0)[0],
+                -1.0,
+                epsilon = 1e-12
+            );
+        }
+        let e = create_raviart_thomas_element(ReferenceCellType::Tetrahedron, 1, false);
+        for face in 0..4 {
+            assert_relative_eq!(e.entity_transformation(2, face, 0)[0], 1.0, epsilon = 1e-12);
+            assert_relative_eq!(
+                e.entity_transformation(2, face, 1)[0],
+                -1.0,
+                epsilon = 1e-12
+            );
+        }
+        let e = create_raviart_thomas_element(ReferenceCellType::Triangle, 1, true);
+        assert_eq!(e.entity_transformation(1, 0, 0), vec![]);
+    }
+
+    #[test]
+    fn test_transformation_orders() {
+        // Reversals and reflections are involutions, and rotating a triangle three times or a
+        // quadrilateral four times gives the identity
+        let elements = [
+            create_lagrange_element(ReferenceCellType::Tetrahedron, 4, false),
+            create_nedelec_element(ReferenceCellType::Tetrahedron, 3, false),
+            create_raviart_thomas_element(ReferenceCellType::Tetrahedron, 3, false),
+            create_nedelec_second_kind_element(ReferenceCellType::Tetrahedron, 2, false),
+            create_regge_element(ReferenceCellType::Tetrahedron, 2, false),
+            create_hhj_element(ReferenceCellType::Tetrahedron, 2, false),
+            create_lagrange_element(ReferenceCellType::Hexahedron, 3, false),
+            create_nce_element(ReferenceCellType::Hexahedron, 2, false),
+            create_rtc_element(ReferenceCellType::Hexahedron, 2, false),
+        ];
+        for e in elements {
+            let cell = create_cell(e.cell_type());
+            for edge in 0..cell.edge_count() {
+                let n = e.entity_dofs(1, edge).len();
+                let m = e.entity_transformation(1, edge, 0);
+                assert_identity(&matmul(&m, &m, n), n);
+            }
+            for face in 0..cell.face_count() {
+                let n = e.entity_dofs(2, face).len();
+                let rotation = e.entity_transformation(2, face, 0);
+                let reflection = e.entity_transformation(2, face, 1);
+                assert_identity(&matmul(&reflection, &reflection, n), n);
+                let mut m = rotation.clone();
+                for _ in 1..cell.connectivity(2, face, 0).unwrap().len() {
+                    m = matmul(&m, &rotation, n);
+                }
+                assert_identity(&m, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_t_apply() {
+        let e = create_nedelec_element(ReferenceCellType::Tetrahedron, 3, false);
+        let dim = e.dim();
+        let cell_info = (0b10_1001 << 12) | (0b110 << 9) | (0b011 << 6) | (0b100 << 3) | 0b101;
+        let mut identity = vec![0.0; dim * dim];
+        for i in 0..dim {
+            identity[i * dim + i] = 1.0;
+        }
+
+        let mut t = identity.clone();
+        e.t_apply(&mut t, dim, cell_info);
+        let mut data = t.clone();
+        e.t_inv_apply(&mut data, dim, cell_info);
+        assert_identity(&data, dim);
+
+        let mut t_transpose = identity.clone();
+        e.t_transpose_apply(&mut t_transpose, dim, cell_info);
+        let mut t_inv_transpose = identity.clone();
+        e.t_inv_transpose_apply(&mut t_inv_transpose, dim, cell_info);
+        for i in 0..dim {
+            for j in 0..dim {
+                assert_relative_eq!(t_transpose[i * dim + j], t[j * dim + i], epsilon = 1e-10);
+            }
+        }
+        assert_identity(&matmul(&t_transpose, &t_inv_transpose, dim), dim);
+
+        // The transformation of face 1, which is rotated twice and not reflected
+        let base = e.base_transformations();
+        assert_eq!(base.len(), 14);
+        let rotation = &base[6 + 2];
+        let dofs = e.entity_dofs(2, 1);
+        let r2 = matmul(rotation, rotation, dim);
+        for i in &dofs {
+            for j in &dofs {
+                assert_relative_eq!(t[i * dim + j], r2[i * dim + j], epsilon = 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shared_edge_continuity() {
+        // Two triangles that share the edge between vertices 1 and 2. For every numbering of the
+        // vertices of each cell, the basis functions transformed using the orientation of the
+        // cells agree on the shared edge: the values for Lagrange elements, the tangential
+        // components for Nédélec elements and the normal components for Raviart-Thomas elements.
+        let coords = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]];
+        let points = [0.2, 0.8, 0.5, 0.5, 0.7, 0.3];
+        let npts = points.len() / 2;
+        let ref_cell = Triangle {};
+        let coord_e = create_lagrange_element(ReferenceCellType::Triangle, 1, false);
+        let orderings = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+
+        for (e, direction) in [
+            (
+                create_lagrange_element(ReferenceCellType::Triangle, 4, false),
+                [1.0, 0.0],
+            ),
+            (
+                create_nedelec_element(ReferenceCellType::Triangle, 3, false),
+                [-1.0, 1.0],
+            ),
+            (
+                create_raviart_thomas_element(ReferenceCellType::Triangle, 3, false),
+                [1.0, 1.0],
+            ),
+        ] {
+            // The transformed values of the basis functions at the points on a cell
+            let values_on_cell = |global_vertices: &[usize]| {
+                let vertices = global_vertices
+                    .iter()
+                    .flat_map(|v| coords[*v])
+                    .collect::<Vec<_>>();
+                let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+                let ref_points = geometry.pull_back_points(&points).unwrap();
+                let mut table = TabulatedData::new(&e, 0, npts);
+                e.tabulate(&ref_points, 0, &mut table);
+                let mut physical = TabulatedData::new(&e, 0, npts);
+                push_forward(&e, &table, &ref_points, &geometry, &mut physical);
+                let mut data = vec![0.0; e.dim() * npts];
+                for i in 0..e.dim() {
+                    for p in 0..npts {
+                        data[i * npts + p] = if e.value_size() == 1 {
+                            *physical.get(0, p, i, 0)
+                        } else {
+                            (0..2)
+                                .map(|v| physical.get(0, p, i, v) * direction[v])
+                                .sum()
+                        };
+                    }
+                }
+                e.t_inv_transpose_apply(
+                    &mut data,
+                    npts,
+                    compute_cell_info(&ref_cell, global_vertices),
+                );
+                data
+            };
+            // The DOFs associated with a sub-entity of the shared edge, given by its global
+            // vertices
+            let shared_dofs = |global_vertices: &[usize], entity: &[usize]| {
+                let d = entity.len() - 1;
+                let i = (0..ref_cell.entity_count(d).unwrap())
+                    .position(|i| {
+                        let mut v = ref_cell
+                            .connectivity(d, i, 0)
+                            .unwrap()
+                            .iter()
+                            .map(|v| global_vertices[*v])
+                            .collect::<Vec<_>>();
+                        v.sort();
+                        v == entity
+                    })
+                    .unwrap();
+                e.entity_dofs(d, i)
+            };
+
+            for cell0 in orderings {
+                let data0 = values_on_cell(&cell0);
+                for order1 in orderings {
+                    let cell1 = order1.map(|i| i + 1);
+                    let data1 = values_on_cell(&cell1);
+                    for entity in [vec![1], vec![2], vec![1, 2]] {
+                        let dofs0 = shared_dofs(&cell0, &entity);
+                        let dofs1 = shared_dofs(&cell1, &entity);
+                        assert_eq!(dofs0.len(), dofs1.len());
+                        for (d0, d1) in dofs0.iter().zip(&dofs1) {
+                            for p in 0..npts {
+                                assert_relative_eq!(
+                                    data0[d0 * npts + p],
+                                    data1[d1 * npts + p],
+                                    epsilon = 1e-10
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_permute_dofs() {
+        for (e, vertices) in [
+            (
+                create_lagrange_element(ReferenceCellType::Tetrahedron, 5, false),
+                vec![3, 0, 2, 1],
+            ),
+            (
+                create_lagrange_element(ReferenceCellType::Hexahedron, 4, false),
+                vec![5, 2, 7, 0, 4, 1, 3, 6],
+            ),
+        ] {
+            assert!(e.dof_transformations_are_permutations());
+            let dim = e.dim();
+            let cell_info = compute_cell_info(create_cell(e.cell_type()).as_ref(), &vertices);
+
+            // Permuting the DOF numbers gives the same result as applying T to them
+            let mut dofs = (0..dim).collect::<Vec<_>>();
+            e.permute_dofs(&mut dofs, cell_info);
+            let mut data = (0..dim).map(|i| i as f64).collect::<Vec<_>>();
+            e.t_apply(&mut data, 1, cell_info);
+            for (d, x) in dofs.iter().zip(&data) {
+                assert_relative_eq!(*d as f64, x, epsilon = 1e-10);
+            }
+            assert!(dofs != (0..dim).collect::<Vec<_>>());
+
+            e.unpermute_dofs(&mut dofs, cell_info);
+            assert_eq!(dofs, (0..dim).collect::<Vec<_>>());
+        }
+        let e = create_nedelec_element(ReferenceCellType::Tetrahedron, 2, false);
+        assert!(!e.dof_transformations_are_permutations());
+        let e = create_lagrange_element(ReferenceCellType::Triangle, 4, false);
+        assert!(e.dof_transformations_are_permutations());
+    }
+}
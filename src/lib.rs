@@ -1,4 +1,7 @@
 pub mod c_api;
 pub mod cell;
 pub mod element;
+mod linalg;
 pub mod map;
+pub mod polyset;
+pub mod quadrature;
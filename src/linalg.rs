@@ -0,0 +1,190 @@
+//! Small dense linear algebra routines used when constructing elements
+
+/// Invert the `n` by `n` row-major matrix `matrix`
+///
+/// Gauss-Jordan elimination with partial pivoting is used. This panics if the matrix is singular.
+pub(crate) fn inverse(matrix: &[f64], n: usize) -> Vec<f64> {
+    assert_eq!(matrix.len(), n * n);
+    let mut a = matrix.to_vec();
+    let mut inv = vec![0.0; n * n];
+    for i in 0..n {
+        inv[i * n + i] = 1.0;
+    }
+
+    for col in 0..n {
+        let mut pivot = col;
+        for row in col + 1..n {
+            if a[row * n + col].abs() > a[pivot * n + col].abs() {
+                pivot = row;
+            }
+        }
+        if a[pivot * n + col].abs() < 1e-14 {
+            panic!("Matrix is singular");
+        }
+        if pivot != col {
+            for j in 0..n {
+                a.swap(col * n + j, pivot * n + j);
+                inv.swap(col * n + j, pivot * n + j);
+            }
+        }
+
+        let scale = 1.0 / a[col * n + col];
+        for j in 0..n {
+            a[col * n + j] *= scale;
+            inv[col * n + j] *= scale;
+        }
+
+        for row in 0..n {
+            if row != col {
+                let factor = a[row * n + col];
+                if factor != 0.0 {
+                    for j in 0..n {
+                        a[row * n + j] -= factor * a[col * n + j];
+                        inv[row * n + j] -= factor * inv[col * n + j];
+                    }
+                }
+            }
+        }
+    }
+    inv
+}
+
+/// Compute the determinant and inverse of a 1 by 1, 2 by 2 or 3 by 3 row-major matrix
+///
+/// The inverse is written into `inverse` and the determinant is returned.
+pub(crate) fn det_and_inverse(m: &[f64], n: usize, inverse: &mut [f64]) -> f64 {
+    match n {
+        1 => {
+            inverse[0] = 1.0 / m[0];
+            m[0]
+        }
+        2 => {
+            let det = m[0] * m[3] - m[1] * m[2];
+            inverse[..4].copy_from_slice(&[m[3] / det, -m[1] / det, -m[2] / det, m[0] / det]);
+            det
+        }
+        3 => {
+            let adj = [
+                m[4] * m[8] - m[5] * m[7],
+                m[2] * m[7] - m[1] * m[8],
+                m[1] * m[5] - m[2] * m[4],
+                m[5] * m[6] - m[3] * m[8],
+                m[0] * m[8] - m[2] * m[6],
+                m[2] * m[3] - m[0] * m[5],
+                m[3] * m[7] - m[4] * m[6],
+                m[1] * m[6] - m[0] * m[7],
+                m[0] * m[4] - m[1] * m[3],
+            ];
+            let det = m[0] * adj[0] + m[1] * adj[3] + m[2] * adj[6];
+            for (i, a) in inverse[..9].iter_mut().zip(adj) {
+                *i = a / det;
+            }
+            det
+        }
+        _ => panic!("Unsupported matrix size: {n}"),
+    }
+}
+
+/// Compute the determinant and inverse of a gdim by tdim row-major Jacobian matrix
+///
+/// The inverse is written into `inverse` as a tdim by gdim row-major matrix, and the
+/// determinant is returned. If tdim is less than gdim, the determinant is sqrt(det(J^T J)) and
+/// the inverse is the pseudo-inverse (J^T J)^{-1} J^T. This does not allocate.
+pub(crate) fn jacobian_det_and_inverse(
+    j: &[f64],
+    gdim: usize,
+    tdim: usize,
+    inverse: &mut [f64],
+) -> f64 {
+    if tdim == gdim {
+        return det_and_inverse(j, tdim, inverse);
+    }
+    let mut jtj = [0.0; 9];
+    for a in 0..tdim {
+        for b in 0..tdim {
+            jtj[a * tdim + b] = (0..gdim).map(|c| j[c * tdim + a] * j[c * tdim + b]).sum();
+        }
+    }
+    let mut inv = [0.0; 9];
+    let det = det_and_inverse(&jtj, tdim, &mut inv);
+    for a in 0..tdim {
+        for b in 0..gdim {
+            inverse[a * gdim + b] = (0..tdim).map(|c| inv[a * tdim + c] * j[b * tdim + c]).sum();
+        }
+    }
+    det.sqrt()
+}
+
+/// Compute an orthonormal basis of the span of the rows of the row-major matrix `matrix` with
+/// `ncols` columns
+///
+/// The rows are orthonormalised in order using modified Gram-Schmidt, and rows that are (up to
+/// a tolerance) linear combinations of the previous rows are dropped. The result is returned in
+/// row-major format.
+pub(crate) fn orthonormal_row_basis(matrix: &[f64], ncols: usize) -> Vec<f64> {
+    assert_eq!(matrix.len() % ncols, 0);
+    let mut basis: Vec<f64> = vec![];
+    for row in matrix.chunks(ncols) {
+        let norm0 = row.iter().map(|a| a * a).sum::<f64>().sqrt();
+        if norm0 == 0.0 {
+            continue;
+        }
+        let mut r = row.to_vec();
+        for b in basis.chunks(ncols) {
+            let dot = r.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<f64>();
+            for (x, y) in r.iter_mut().zip(b.iter()) {
+                *x -= dot * y;
+            }
+        }
+        let norm = r.iter().map(|a| a * a).sum::<f64>().sqrt();
+        if norm > 1e-10 * norm0 {
+            basis.extend(r.iter().map(|a| a / norm));
+        }
+    }
+    basis
+}
+
+#[cfg(test)]
+mod test {
+    use crate::linalg::*;
+    use approx::*;
+
+    #[test]
+    fn test_inverse() {
+        let m = [2.0, 1.0, 0.0, 1.0, 3.0, 1.0, 0.0, 1.0, 4.0];
+        let inv = inverse(&m, 3);
+        for i in 0..3 {
+            for j in 0..3 {
+                let mut entry = 0.0;
+                for k in 0..3 {
+                    entry += m[i * 3 + k] * inv[k * 3 + j];
+                }
+                assert_relative_eq!(entry, if i == j { 1.0 } else { 0.0 }, epsilon = 1e-14);
+            }
+        }
+    }
+
+    #[test]
+    fn test_det_and_inverse() {
+        let m = [2.0, 1.0, 0.0, 1.0, 3.0, 1.0, 0.0, 1.0, 4.0];
+        let mut inv = [0.0; 9];
+        let det = det_and_inverse(&m, 3, &mut inv);
+        assert_relative_eq!(det, 18.0, epsilon = 1e-14);
+        for (a, b) in inv.iter().zip(inverse(&m, 3)) {
+            assert_relative_eq!(*a, b, epsilon = 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_orthonormal_row_basis() {
+        let m = [1.0, 1.0, 0.0, 2.0, 2.0, 0.0, 0.0, 1.0, 1.0];
+        let b = orthonormal_row_basis(&m, 3);
+        assert_eq!(b.len(), 6);
+        for i in 0..2 {
+            for j in 0..2 {
+                let dot = (0..3).map(|k| b[i * 3 + k] * b[j * 3 + k]).sum::<f64>();
+                assert_relative_eq!(dot, if i == j { 1.0 } else { 0.0 }, epsilon = 1e-14);
+            }
+        }
+    }
+}
@@ -1,7 +1,13 @@
 //! Push forward and pull back maps
+//!
+//! The push forward functions also map first and second derivatives of the values to derivatives
+//! with respect to the physical coordinates, when gdim equals tdim.
 
 use crate::cell::*;
 use crate::element::*;
+use crate::linalg::jacobian_det_and_inverse;
+use crate::polyset::*;
+use std::ops::{Add, Mul, Neg, Sub};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 #[repr(u8)]
@@ -10,333 +16,715 @@ pub enum MapType {
     CovariantPiola = 1,
     ContravariantPiola = 2,
     L2Piola = 3,
+    DoubleCovariantPiola = 4,
+    DoubleContravariantPiola = 5,
 }
 
-pub fn identity_push_forward<'a, 'b, F: FiniteElement, F2: FiniteElement, C: ReferenceCell>(
-    data: &mut TabulatedData<'a, F>,
+/// Push forward using the identity map, u = v
+///
+/// The values are unchanged, and any derivatives are mapped to derivatives with respect to the
+/// physical coordinates.
+///
+/// Derivatives can only be pushed forward if tdim equals gdim: this function panics if `data`
+/// contains derivatives and the cell is a manifold with tdim less than gdim.
+pub fn identity_push_forward<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
     points: &[f64],
     geometry: &PhysicalCell<'b, F2, C>,
 ) {
-    assert_eq!(data.deriv_count(), 1);
+    if data.deriv_count() > 1 {
+        let value_size = data.value_size();
+        push_forward_with_derivatives(data, points, geometry, value_size, |_, a, b| {
+            Jet::constant(if a == b { 1.0 } else { 0.0 })
+        });
+    }
 }
 
-pub fn identity_pull_back<'a, 'b, F: FiniteElement, F2: FiniteElement, C: ReferenceCell>(
-    data: &mut TabulatedData<'a, F>,
-    points: &[f64],
-    geometry: &PhysicalCell<'b, F2, C>,
+pub fn identity_pull_back<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    _points: &[f64],
+    _geometry: &PhysicalCell<'b, F2, C>,
 ) {
     assert_eq!(data.deriv_count(), 1);
 }
 
-pub fn contravariant_piola_push_forward<
-    'a,
-    'b,
-    F: FiniteElement,
-    F2: FiniteElement,
-    C: ReferenceCell,
->(
-    data: &mut TabulatedData<'a, F>,
-    points: &[f64],
-    geometry: &PhysicalCell<'b, F2, C>,
-) {
-    assert_eq!(data.deriv_count(), 1);
+/// A scalar function of the reference coordinates at a point, together with its first and
+/// second derivatives
+#[derive(Debug, Clone, Copy)]
+struct Jet {
+    value: f64,
+    grad: [f64; 3],
+    hess: [[f64; 3]; 3],
+}
 
-    if geometry.tdim() == 2 && geometry.gdim() == 2 {
-        let gdim = geometry.gdim();
-        let npts = points.len() / gdim;
-        let geometry_npts = geometry.npts();
-        let nbasis = data.basis_count();
-
-        // TODO: get rid of memory assignment inside this function
-        let mut derivs = TabulatedData::new(geometry.coordinate_element(), 1, npts);
-        geometry
-            .coordinate_element()
-            .tabulate(&points, 1, &mut derivs);
-
-        let mut j = vec![0.0, 0.0, 0.0, 0.0];
-        let mut det_j = 0.0;
-
-        let mut temp_data = vec![0.0, 0.0];
-
-        for p in 0..npts {
-            j[0] = 0.0;
-            j[1] = 0.0;
-            j[2] = 0.0;
-            j[3] = 0.0;
-            for gp in 0..geometry_npts {
-                j[0] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim];
-                j[1] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim];
-                j[2] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
-                j[3] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
-            }
-            det_j = j[0] * j[3] - j[1] * j[2];
+impl Jet {
+    fn constant(value: f64) -> Self {
+        Self {
+            value,
+            grad: [0.0; 3],
+            hess: [[0.0; 3]; 3],
+        }
+    }
 
-            for i in 0..nbasis {
-                temp_data[0] = *data.get(0, p, i, 0);
-                temp_data[1] = *data.get(0, p, i, 1);
+    fn recip(self) -> Self {
+        let v = self.value;
+        let mut result = Self::constant(1.0 / v);
+        for i in 0..3 {
+            result.grad[i] = -self.grad[i] / (v * v);
+            for j in 0..3 {
+                result.hess[i][j] =
+                    2.0 * self.grad[i] * self.grad[j] / (v * v * v) - self.hess[i][j] / (v * v);
+            }
+        }
+        result
+    }
+}
 
-                *data.get_mut(0, p, i, 0) = (j[0] * temp_data[0] + j[1] * temp_data[1]) / det_j;
-                *data.get_mut(0, p, i, 1) = (j[2] * temp_data[0] + j[3] * temp_data[1]) / det_j;
+impl Add for Jet {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        let mut result = self;
+        result.value += other.value;
+        for i in 0..3 {
+            result.grad[i] += other.grad[i];
+            for j in 0..3 {
+                result.hess[i][j] += other.hess[i][j];
             }
         }
-    } else {
-        unimplemented!("push_forward not yet implemented for this element");
+        result
     }
 }
 
-pub fn contravariant_piola_pull_back<
-    'a,
-    'b,
-    F: FiniteElement,
-    F2: FiniteElement,
-    C: ReferenceCell,
->(
-    data: &mut TabulatedData<'a, F>,
-    points: &[f64],
-    geometry: &PhysicalCell<'b, F2, C>,
-) {
-    assert_eq!(data.deriv_count(), 1);
+impl Neg for Jet {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::constant(0.0) - self
+    }
+}
 
-    if geometry.tdim() == 2 && geometry.gdim() == 2 {
-        let gdim = geometry.gdim();
-        let npts = points.len() / gdim;
-        let geometry_npts = geometry.npts();
-        let nbasis = data.basis_count();
-
-        // TODO: get rid of memory assignment inside this function
-        let mut derivs = TabulatedData::new(geometry.coordinate_element(), 1, npts);
-        geometry
-            .coordinate_element()
-            .tabulate(&points, 1, &mut derivs);
-
-        let mut jinv = vec![0.0, 0.0, 0.0, 0.0];
-        let mut det_j = 0.0;
-
-        let mut temp_data = vec![0.0, 0.0];
-
-        for p in 0..npts {
-            jinv[0] = 0.0;
-            jinv[1] = 0.0;
-            jinv[2] = 0.0;
-            jinv[3] = 0.0;
-            for gp in 0..geometry_npts {
-                jinv[3] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim];
-                jinv[1] -= derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim];
-                jinv[2] -= derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
-                jinv[0] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
+impl Sub for Jet {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        let mut result = self;
+        result.value -= other.value;
+        for i in 0..3 {
+            result.grad[i] -= other.grad[i];
+            for j in 0..3 {
+                result.hess[i][j] -= other.hess[i][j];
             }
+        }
+        result
+    }
+}
 
-            for i in 0..nbasis {
-                temp_data[0] = *data.get(0, p, i, 0);
-                temp_data[1] = *data.get(0, p, i, 1);
-
-                *data.get_mut(0, p, i, 0) = jinv[0] * temp_data[0] + jinv[1] * temp_data[1];
-                *data.get_mut(0, p, i, 1) = jinv[2] * temp_data[0] + jinv[3] * temp_data[1];
+impl Mul for Jet {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let mut result = Self::constant(self.value * other.value);
+        for i in 0..3 {
+            result.grad[i] = self.grad[i] * other.value + self.value * other.grad[i];
+            for j in 0..3 {
+                result.hess[i][j] = self.hess[i][j] * other.value
+                    + self.grad[i] * other.grad[j]
+                    + self.grad[j] * other.grad[i]
+                    + self.value * other.hess[i][j];
             }
         }
-    } else {
-        unimplemented!("push_forward not yet implemented for this element");
+        result
     }
 }
 
-pub fn covariant_piola_push_forward<
-    'a,
-    'b,
-    F: FiniteElement,
-    F2: FiniteElement,
-    C: ReferenceCell,
->(
-    data: &mut TabulatedData<'a, F>,
+/// The Jacobian, its (pseudo-)inverse and its determinant at a point, with their derivatives
+/// with respect to the reference coordinates
+///
+/// J is stored as a gdim by tdim row-major matrix and K as a tdim by gdim row-major matrix.
+struct GeometryJets {
+    j: Vec<Jet>,
+    k: Vec<Jet>,
+    det: Jet,
+}
+
+/// Compute the Jacobian, its inverse and its determinant at each point, with their derivatives up
+/// to order `nderivs`
+///
+/// If `nderivs` is 0, this supports manifolds. Otherwise this panics unless tdim equals gdim,
+/// and the coordinate element is tabulated with `nderivs + 1` derivatives.
+fn geometry_jets<F2: FiniteElement + ?Sized, C: ReferenceCell>(
     points: &[f64],
-    geometry: &PhysicalCell<'b, F2, C>,
-) {
-    assert_eq!(data.deriv_count(), 1);
+    nderivs: usize,
+    geometry: &PhysicalCell<F2, C>,
+) -> Vec<GeometryJets> {
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    if nderivs == 0 {
+        let (j, dets, k) = geometry.jacobians_determinants_and_inverses(points);
+        return j
+            .chunks(gdim * tdim)
+            .zip(k.chunks(tdim * gdim))
+            .zip(dets)
+            .map(|((j, k), det)| GeometryJets {
+                j: j.iter().map(|v| Jet::constant(*v)).collect(),
+                k: k.iter().map(|v| Jet::constant(*v)).collect(),
+                det: Jet::constant(det),
+            })
+            .collect();
+    }
+    assert_eq!(
+        tdim, gdim,
+        "Derivatives can only be pushed forward on cells where tdim equals gdim"
+    );
+
+    let npts = points.len() / tdim;
+    let coordinate_element = geometry.coordinate_element();
+    let mut table = TabulatedData::new(coordinate_element, nderivs + 1, npts);
+    coordinate_element.tabulate(points, nderivs + 1, &mut table);
+    // The derivative of the geometry map with the given orders in the reference directions
+    let derivative = |p: usize, a: usize, orders: [usize; 3]| {
+        (0..geometry.npts())
+            .map(|gp| {
+                table.get(derivative_index(tdim, &orders), p, gp, 0)
+                    * geometry.vertices()[gp * gdim + a]
+            })
+            .sum::<f64>()
+    };
+
+    (0..npts)
+        .map(|p| {
+            let mut j = vec![Jet::constant(0.0); gdim * tdim];
+            for a in 0..gdim {
+                for b in 0..tdim {
+                    let jet = &mut j[a * tdim + b];
+                    let mut orders = [0; 3];
+                    orders[b] += 1;
+                    jet.value = derivative(p, a, orders);
+                    for l in 0..tdim {
+                        orders[l] += 1;
+                        jet.grad[l] = derivative(p, a, orders);
+                        if nderivs > 1 {
+                            for m in 0..tdim {
+                                orders[m] += 1;
+                                jet.hess[l][m] = derivative(p, a, orders);
+                                orders[m] -= 1;
+                            }
+                        }
+                        orders[l] -= 1;
+                    }
+                }
+            }
+            let (det, adj) = match tdim {
+                1 => (j[0], vec![Jet::constant(1.0)]),
+                2 => (j[0] * j[3] - j[1] * j[2], vec![j[3], -j[1], -j[2], j[0]]),
+                3 => {
+                    let adj = vec![
+                        j[4] * j[8] - j[5] * j[7],
+                        j[2] * j[7] - j[1] * j[8],
+                        j[1] * j[5] - j[2] * j[4],
+                        j[5] * j[6] - j[3] * j[8],
+                        j[0] * j[8] - j[2] * j[6],
+                        j[2] * j[3] - j[0] * j[5],
+                        j[3] * j[7] - j[4] * j[6],
+                        j[1] * j[6] - j[0] * j[7],
+                        j[0] * j[4] - j[1] * j[3],
+                    ];
+                    (j[0] * adj[0] + j[1] * adj[3] + j[2] * adj[6], adj)
+                }
+                _ => panic!("Unsupported dimension: {tdim}"),
+            };
+            let inv_det = det.recip();
+            GeometryJets {
+                j,
+                k: adj.into_iter().map(|a| a * inv_det).collect(),
+                det,
+            }
+        })
+        .collect()
+}
 
-    if geometry.tdim() == 2 && geometry.gdim() == 2 {
-        let gdim = geometry.gdim();
-        let npts = points.len() / gdim;
-        let geometry_npts = geometry.npts();
-        let nbasis = data.basis_count();
-
-        // TODO: get rid of memory assignment inside this function
-        let mut derivs = TabulatedData::new(geometry.coordinate_element(), 1, npts);
-        geometry
-            .coordinate_element()
-            .tabulate(&points, 1, &mut derivs);
-
-        let mut jinv_t = vec![0.0, 0.0, 0.0, 0.0];
-        let mut det_j = 0.0;
-
-        let mut temp_data = vec![0.0, 0.0];
-
-        for p in 0..npts {
-            jinv_t[0] = 0.0;
-            jinv_t[1] = 0.0;
-            jinv_t[2] = 0.0;
-            jinv_t[3] = 0.0;
-            for gp in 0..geometry_npts {
-                jinv_t[3] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim];
-                jinv_t[2] -= derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim];
-                jinv_t[1] -= derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
-                jinv_t[0] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
+/// Replace each vector value v in `data` by u = M v, where M is the matrix for each point given
+/// by `matrix` and the matrix has `nrows` rows, and push forward the derivatives of u
+///
+/// First and second derivatives are supported. The derivatives of u with respect to the
+/// reference coordinates include the derivatives of M, which are non-zero for non-affine
+/// geometries; these are then mapped to physical derivatives using K and its derivatives.
+///
+/// The value size of `data` is changed to `nrows`.
+fn push_forward_with_derivatives<F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<F2, C>,
+    nrows: usize,
+    matrix: impl Fn(&GeometryJets, usize, usize) -> Jet,
+) {
+    let cell_type = geometry.reference_cell().cell_type();
+    let tdim = geometry.tdim();
+    let nderivs = (0..3)
+        .find(|n| derivative_count(cell_type, *n) == data.deriv_count())
+        .expect("Only first and second derivatives can be pushed forward");
+    let jets = geometry_jets(points, nderivs, geometry);
+
+    let npts = data.point_count();
+    let nbasis = data.basis_count();
+    let ncols = data.value_size();
+    let deriv = |orders: &[usize]| {
+        let mut o = [0; 3];
+        for i in orders {
+            o[*i] += 1;
+        }
+        derivative_index(tdim, &o)
+    };
+    let mut values = vec![0.0; data.deriv_count() * npts * nbasis * nrows];
+    for (p, g) in jets.iter().enumerate() {
+        for i in 0..nbasis {
+            for a in 0..nrows {
+                // The mapped function and its derivatives with respect to the reference
+                // coordinates
+                let mut u = Jet::constant(0.0);
+                for b in 0..ncols {
+                    let mut v = Jet::constant(*data.get(0, p, i, b));
+                    if nderivs > 0 {
+                        for l in 0..tdim {
+                            v.grad[l] = *data.get(deriv(&[l]), p, i, b);
+                            if nderivs > 1 {
+                                for m in 0..tdim {
+                                    v.hess[l][m] = *data.get(deriv(&[l, m]), p, i, b);
+                                }
+                            }
+                        }
+                    }
+                    u = u + matrix(g, a, b) * v;
+                }
+
+                let index = |d: usize| ((d * npts + p) * nbasis + i) * nrows + a;
+                values[index(0)] = u.value;
+                if nderivs > 0 {
+                    for x in 0..tdim {
+                        values[index(deriv(&[x]))] =
+                            (0..tdim).map(|l| g.k[l * tdim + x].value * u.grad[l]).sum();
+                    }
+                }
+                if nderivs > 1 {
+                    for x in 0..tdim {
+                        for y in x..tdim {
+                            let mut value = 0.0;
+                            for l in 0..tdim {
+                                for m in 0..tdim {
+                                    value += g.k[l * tdim + x].value
+                                        * g.k[m * tdim + y].value
+                                        * u.hess[l][m]
+                                        + g.k[m * tdim + y].value
+                                            * g.k[l * tdim + x].grad[m]
+                                            * u.grad[l];
+                                }
+                            }
+                            values[index(deriv(&[x, y]))] = value;
+                        }
+                    }
+                }
             }
-            det_j = jinv_t[0] * jinv_t[3] - jinv_t[1] * jinv_t[2];
-            jinv_t[0] /= det_j;
-            jinv_t[1] /= det_j;
-            jinv_t[2] /= det_j;
-            jinv_t[3] /= det_j;
-
-            for i in 0..nbasis {
-                temp_data[0] = *data.get(0, p, i, 0);
-                temp_data[1] = *data.get(0, p, i, 1);
-
-                *data.get_mut(0, p, i, 0) = jinv_t[0] * temp_data[0] + jinv_t[1] * temp_data[1];
-                *data.get_mut(0, p, i, 1) = jinv_t[2] * temp_data[0] + jinv_t[3] * temp_data[1];
+        }
+    }
+    data.set_values(nrows, values);
+}
+
+/// Replace each vector value v in `data` by M v, where M is the matrix for each point given by
+/// `matrix` and the matrix has `nrows` rows
+///
+/// The value size of `data` is changed to `nrows`.
+fn apply_matrices(
+    data: &mut TabulatedData,
+    nrows: usize,
+    matrix: impl Fn(usize, usize, usize) -> f64,
+) {
+    let npts = data.point_count();
+    let nbasis = data.basis_count();
+    let ncols = data.value_size();
+    let mut values = vec![0.0; npts * nbasis * nrows];
+    for p in 0..npts {
+        for i in 0..nbasis {
+            for a in 0..nrows {
+                values[(p * nbasis + i) * nrows + a] = (0..ncols)
+                    .map(|b| matrix(p, a, b) * data.get(0, p, i, b))
+                    .sum();
             }
         }
-    } else {
-        unimplemented!("push_forward not yet implemented for this element");
     }
+    data.set_values(nrows, values);
+}
+
+/// Push forward using the contravariant Piola map, u = J v / det(J)
+///
+/// After calling this function, the value size of `data` is gdim.
+/// Derivatives can only be pushed forward if tdim equals gdim: this function panics if `data`
+/// contains derivatives and the cell is a manifold with tdim less than gdim.
+pub fn contravariant_piola_push_forward<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+) {
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    assert_eq!(data.value_size(), tdim);
+    push_forward_with_derivatives(data, points, geometry, gdim, |g, a, b| {
+        g.j[a * tdim + b] * g.det.recip()
+    });
 }
 
-pub fn covariant_piola_pull_back<'a, 'b, F: FiniteElement, F2: FiniteElement, C: ReferenceCell>(
-    data: &mut TabulatedData<'a, F>,
+/// Pull back using the contravariant Piola map, v = det(J) K u, where K is the (pseudo-)inverse
+/// of J
+///
+/// After calling this function, the value size of `data` is tdim.
+pub fn contravariant_piola_pull_back<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
     points: &[f64],
     geometry: &PhysicalCell<'b, F2, C>,
 ) {
     assert_eq!(data.deriv_count(), 1);
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    assert_eq!(data.value_size(), gdim);
+    let (_, det_j, k) = geometry.jacobians_determinants_and_inverses(points);
+    apply_matrices(data, tdim, |p, a, b| {
+        det_j[p] * k[(p * tdim + a) * gdim + b]
+    });
+}
 
-    if geometry.tdim() == 2 && geometry.gdim() == 2 {
-        let gdim = geometry.gdim();
-        let npts = points.len() / gdim;
-        let geometry_npts = geometry.npts();
-        let nbasis = data.basis_count();
-
-        // TODO: get rid of memory assignment inside this function
-        let mut derivs = TabulatedData::new(geometry.coordinate_element(), 1, npts);
-        geometry
-            .coordinate_element()
-            .tabulate(&points, 1, &mut derivs);
-
-        let mut j_t = vec![0.0, 0.0, 0.0, 0.0];
-        let mut det_j = 0.0;
-
-        let mut temp_data = vec![0.0, 0.0];
-
-        for p in 0..npts {
-            j_t[0] = 0.0;
-            j_t[1] = 0.0;
-            j_t[2] = 0.0;
-            j_t[3] = 0.0;
-            for gp in 0..geometry_npts {
-                j_t[0] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim];
-                j_t[2] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim];
-                j_t[1] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
-                j_t[3] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
-            }
+/// Push forward using the covariant Piola map, u = K^T v, where K is the (pseudo-)inverse of J
+///
+/// After calling this function, the value size of `data` is gdim.
+/// Derivatives can only be pushed forward if tdim equals gdim: this function panics if `data`
+/// contains derivatives and the cell is a manifold with tdim less than gdim.
+pub fn covariant_piola_push_forward<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+) {
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    assert_eq!(data.value_size(), tdim);
+    push_forward_with_derivatives(data, points, geometry, gdim, |g, a, b| g.k[b * gdim + a]);
+}
 
-            for i in 0..nbasis {
-                temp_data[0] = *data.get(0, p, i, 0);
-                temp_data[1] = *data.get(0, p, i, 1);
+/// Pull back using the covariant Piola map, v = J^T u
+///
+/// After calling this function, the value size of `data` is tdim.
+pub fn covariant_piola_pull_back<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+) {
+    assert_eq!(data.deriv_count(), 1);
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    assert_eq!(data.value_size(), gdim);
+    let (j, _, _) = geometry.jacobians_determinants_and_inverses(points);
+    apply_matrices(data, tdim, |p, a, b| j[(p * gdim + b) * tdim + a]);
+}
 
-                *data.get_mut(0, p, i, 0) = j_t[0] * temp_data[0] + j_t[1] * temp_data[1];
-                *data.get_mut(0, p, i, 1) = j_t[2] * temp_data[0] + j_t[3] * temp_data[1];
-            }
+/// Push forward using the L2 Piola map, u = det(J) v
+///
+/// Derivatives can only be pushed forward if tdim equals gdim: this function panics if `data`
+/// contains derivatives and the cell is a manifold with tdim less than gdim.
+pub fn l2_piola_push_forward<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+) {
+    let value_size = data.value_size();
+    push_forward_with_derivatives(data, points, geometry, value_size, |g, a, b| {
+        if a == b {
+            g.det
+        } else {
+            Jet::constant(0.0)
         }
-    } else {
-        unimplemented!("push_forward not yet implemented for this element");
-    }
+    });
 }
 
-pub fn l2_piola_push_forward<'a, 'b, F: FiniteElement, F2: FiniteElement, C: ReferenceCell>(
-    data: &mut TabulatedData<'a, F>,
+/// Pull back using the L2 Piola map, v = u / det(J)
+pub fn l2_piola_pull_back<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
     points: &[f64],
     geometry: &PhysicalCell<'b, F2, C>,
 ) {
     assert_eq!(data.deriv_count(), 1);
-
-    if geometry.tdim() == 2 && geometry.gdim() == 2 {
-        let gdim = geometry.gdim();
-        let npts = points.len() / gdim;
-        let geometry_npts = geometry.npts();
-        let nbasis = data.basis_count();
-
-        // TODO: get rid of memory assignment inside this function
-        let mut derivs = TabulatedData::new(geometry.coordinate_element(), 1, npts);
-        geometry
-            .coordinate_element()
-            .tabulate(&points, 1, &mut derivs);
-
-        let mut j = vec![0.0, 0.0, 0.0, 0.0];
-        let mut det_j = 0.0;
-
-        let mut temp_data = vec![0.0, 0.0];
-
-        for p in 0..npts {
-            j[0] = 0.0;
-            j[1] = 0.0;
-            j[2] = 0.0;
-            j[3] = 0.0;
-            for gp in 0..geometry_npts {
-                j[0] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim];
-                j[1] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim];
-                j[2] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
-                j[3] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
+    let (_, det_j, _) = geometry.jacobians_determinants_and_inverses(points);
+    let value_size = data.value_size();
+    apply_matrices(
+        data,
+        value_size,
+        |p, a, b| {
+            if a == b {
+                1.0 / det_j[p]
+            } else {
+                0.0
             }
-            det_j = j[0] * j[3] - j[1] * j[2];
+        },
+    );
+}
 
-            for i in 0..nbasis {
-                *data.get_mut(0, p, i, 0) *= det_j;
-            }
+/// Push forward using the double covariant Piola map, U = K^T S K, where K is the
+/// (pseudo-)inverse of J
+///
+/// The values in `data` are tdim by tdim matrices stored in row-major order. After calling this
+/// function, the value size of `data` is gdim * gdim.
+/// Derivatives can only be pushed forward if tdim equals gdim: this function panics if `data`
+/// contains derivatives and the cell is a manifold with tdim less than gdim.
+pub fn double_covariant_piola_push_forward<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+) {
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    assert_eq!(data.value_size(), tdim * tdim);
+    push_forward_with_derivatives(data, points, geometry, gdim * gdim, |g, row, col| {
+        let (a, b) = (row / gdim, row % gdim);
+        let (c, d) = (col / tdim, col % tdim);
+        g.k[c * gdim + a] * g.k[d * gdim + b]
+    });
+}
+
+/// Pull back using the double covariant Piola map, S = J^T U J
+///
+/// After calling this function, the value size of `data` is tdim * tdim.
+pub fn double_covariant_piola_pull_back<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+) {
+    assert_eq!(data.deriv_count(), 1);
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    assert_eq!(data.value_size(), gdim * gdim);
+    let (j, _, _) = geometry.jacobians_determinants_and_inverses(points);
+    apply_matrices(data, tdim * tdim, |p, row, col| {
+        let (a, b) = (row / tdim, row % tdim);
+        let (c, d) = (col / gdim, col % gdim);
+        j[(p * gdim + c) * tdim + a] * j[(p * gdim + d) * tdim + b]
+    });
+}
+
+/// Push forward using the double contravariant Piola map, U = J S J^T / det(J)^2
+///
+/// The values in `data` are tdim by tdim matrices stored in row-major order. After calling this
+/// function, the value size of `data` is gdim * gdim.
+/// Derivatives can only be pushed forward if tdim equals gdim: this function panics if `data`
+/// contains derivatives and the cell is a manifold with tdim less than gdim.
+pub fn double_contravariant_piola_push_forward<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+) {
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    assert_eq!(data.value_size(), tdim * tdim);
+    push_forward_with_derivatives(data, points, geometry, gdim * gdim, |g, row, col| {
+        let (a, b) = (row / gdim, row % gdim);
+        let (c, d) = (col / tdim, col % tdim);
+        g.j[a * tdim + c] * g.j[b * tdim + d] * (g.det * g.det).recip()
+    });
+}
+
+/// Pull back using the double contravariant Piola map, S = det(J)^2 K U K^T, where K is the
+/// (pseudo-)inverse of J
+///
+/// After calling this function, the value size of `data` is tdim * tdim.
+pub fn double_contravariant_piola_pull_back<'b, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    data: &mut TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+) {
+    assert_eq!(data.deriv_count(), 1);
+    let tdim = geometry.tdim();
+    let gdim = geometry.gdim();
+    assert_eq!(data.value_size(), gdim * gdim);
+    let (_, det_j, k) = geometry.jacobians_determinants_and_inverses(points);
+    apply_matrices(data, tdim * tdim, |p, row, col| {
+        let (a, b) = (row / tdim, row % tdim);
+        let (c, d) = (col / gdim, col % gdim);
+        det_j[p] * det_j[p] * k[(p * tdim + a) * gdim + c] * k[(p * tdim + b) * gdim + d]
+    });
+}
+
+/// Push forward the values in `reference_values` using the map of `element`, writing the result
+/// into `physical_values`
+///
+/// `reference_values` is not modified, so a single reference tabulation can be pushed forward to
+/// many cells. The shape and values of `physical_values` are overwritten.
+///
+/// Derivatives can only be pushed forward if tdim equals gdim: this function panics if
+/// `reference_values` contains derivatives and the cell is a manifold with tdim less than gdim.
+pub fn push_forward<'b, F: FiniteElement + ?Sized, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    element: &F,
+    reference_values: &TabulatedData,
+    points: &[f64],
+    geometry: &PhysicalCell<'b, F2, C>,
+    physical_values: &mut TabulatedData,
+) {
+    assert_eq!(reference_values.basis_count(), element.dim());
+    assert_eq!(reference_values.value_size(), element.value_size());
+    assert_eq!(element.cell_type(), geometry.reference_cell().cell_type());
+    physical_values.copy_from(reference_values);
+    match element.map_type() {
+        MapType::Identity => identity_push_forward(physical_values, points, geometry),
+        MapType::CovariantPiola => covariant_piola_push_forward(physical_values, points, geometry),
+        MapType::ContravariantPiola => {
+            contravariant_piola_push_forward(physical_values, points, geometry)
+        }
+        MapType::L2Piola => l2_piola_push_forward(physical_values, points, geometry),
+        MapType::DoubleCovariantPiola => {
+            double_covariant_piola_push_forward(physical_values, points, geometry)
+        }
+        MapType::DoubleContravariantPiola => {
+            double_contravariant_piola_push_forward(physical_values, points, geometry)
         }
-    } else {
-        unimplemented!("push_forward not yet implemented for this element");
     }
 }
 
-pub fn l2_piola_pull_back<'a, 'b, F: FiniteElement, F2: FiniteElement, C: ReferenceCell>(
-    data: &mut TabulatedData<'a, F>,
+/// Pull back the values in `physical_values` using the map of `element`, writing the result into
+/// `reference_values`
+///
+/// `physical_values` is not modified. The shape and values of `reference_values` are
+/// overwritten.
+pub fn pull_back<'b, F: FiniteElement + ?Sized, F2: FiniteElement + ?Sized, C: ReferenceCell>(
+    element: &F,
+    physical_values: &TabulatedData,
     points: &[f64],
     geometry: &PhysicalCell<'b, F2, C>,
+    reference_values: &mut TabulatedData,
 ) {
-    assert_eq!(data.deriv_count(), 1);
+    assert_eq!(element.cell_type(), geometry.reference_cell().cell_type());
+    reference_values.copy_from(physical_values);
+    match element.map_type() {
+        MapType::Identity => identity_pull_back(reference_values, points, geometry),
+        MapType::CovariantPiola => covariant_piola_pull_back(reference_values, points, geometry),
+        MapType::ContravariantPiola => {
+            contravariant_piola_pull_back(reference_values, points, geometry)
+        }
+        MapType::L2Piola => l2_piola_pull_back(reference_values, points, geometry),
+        MapType::DoubleCovariantPiola => {
+            double_covariant_piola_pull_back(reference_values, points, geometry)
+        }
+        MapType::DoubleContravariantPiola => {
+            double_contravariant_piola_pull_back(reference_values, points, geometry)
+        }
+    }
+    assert_eq!(reference_values.value_size(), element.value_size());
+}
+
+/// The value size of the pushed forward values of an element with the given map type and
+/// reference value size, on a cell with geometric dimension `gdim`
+pub fn physical_value_size(map_type: MapType, value_size: usize, gdim: usize) -> usize {
+    match map_type {
+        MapType::Identity | MapType::L2Piola => value_size,
+        MapType::CovariantPiola | MapType::ContravariantPiola => gdim,
+        MapType::DoubleCovariantPiola | MapType::DoubleContravariantPiola => gdim * gdim,
+    }
+}
 
-    if geometry.tdim() == 2 && geometry.gdim() == 2 {
-        let gdim = geometry.gdim();
-        let npts = points.len() / gdim;
-        let geometry_npts = geometry.npts();
-        let nbasis = data.basis_count();
-
-        // TODO: get rid of memory assignment inside this function
-        let mut derivs = TabulatedData::new(geometry.coordinate_element(), 1, npts);
-        geometry
-            .coordinate_element()
-            .tabulate(&points, 1, &mut derivs);
-
-        let mut j = vec![0.0, 0.0, 0.0, 0.0];
-        let mut det_j = 0.0;
-
-        let mut temp_data = vec![0.0, 0.0];
-
-        for p in 0..npts {
-            j[0] = 0.0;
-            j[1] = 0.0;
-            j[2] = 0.0;
-            j[3] = 0.0;
-            for gp in 0..geometry_npts {
-                j[0] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim];
-                j[1] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim];
-                j[2] += derivs.get(1, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
-                j[3] += derivs.get(2, p, gp, 0) * geometry.vertices()[gp * gdim + 1];
+/// The entry in row `row` and column `col` of the matrix M such that the pushed forward value
+/// is M v
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn map_matrix_entry(
+    map_type: MapType,
+    j: &[f64],
+    k: &[f64],
+    det: f64,
+    gdim: usize,
+    tdim: usize,
+    row: usize,
+    col: usize,
+) -> f64 {
+    match map_type {
+        MapType::Identity => {
+            if row == col {
+                1.0
+            } else {
+                0.0
             }
-            det_j = j[0] * j[3] - j[1] * j[2];
+        }
+        MapType::L2Piola => {
+            if row == col {
+                det
+            } else {
+                0.0
+            }
+        }
+        MapType::ContravariantPiola => j[row * tdim + col] / det,
+        MapType::CovariantPiola => k[col * gdim + row],
+        MapType::DoubleCovariantPiola => {
+            let (a, b) = (row / gdim, row % gdim);
+            let (c, d) = (col / tdim, col % tdim);
+            k[c * gdim + a] * k[d * gdim + b]
+        }
+        MapType::DoubleContravariantPiola => {
+            let (a, b) = (row / gdim, row % gdim);
+            let (c, d) = (col / tdim, col % tdim);
+            j[a * tdim + c] * j[b * tdim + d] / (det * det)
+        }
+    }
+}
 
-            for i in 0..nbasis {
-                *data.get_mut(0, p, i, 0) /= det_j;
+/// Push forward the values in `reference_values` to many cells at once using the map of
+/// `element`
+///
+/// `coordinate_table` is the tabulation of the coordinate element at the same points, with at
+/// least one derivative. `geometries` contains the coordinates of the nodes of the coordinate
+/// element for each cell, with shape (cell, node, gdim). The pushed forward values are written
+/// into `physical_values`, with shape (cell, point, basis, component): the number of components
+/// is given by [physical_value_size].
+///
+/// Only values can be pushed forward by this function; derivatives can be pushed forward one
+/// cell at a time using [push_forward]. This function does not allocate.
+pub fn push_forward_batch<F: FiniteElement + ?Sized>(
+    element: &F,
+    reference_values: &TabulatedData,
+    coordinate_table: &TabulatedData,
+    gdim: usize,
+    geometries: &[f64],
+    physical_values: &mut [f64],
+) {
+    let tdim = create_cell(element.cell_type()).dim();
+    let map_type = element.map_type();
+    let npts = reference_values.point_count();
+    let nbasis = reference_values.basis_count();
+    let value_size = reference_values.value_size();
+    let pvs = physical_value_size(map_type, value_size, gdim);
+    let nnodes = coordinate_table.basis_count();
+    let ncells = geometries.len() / (nnodes * gdim);
+    assert_eq!(reference_values.deriv_count(), 1);
+    assert_eq!(nbasis, element.dim());
+    assert_eq!(value_size, element.value_size());
+    assert!(coordinate_table.deriv_count() > tdim);
+    assert_eq!(coordinate_table.point_count(), npts);
+    assert_eq!(geometries.len(), ncells * nnodes * gdim);
+    assert_eq!(physical_values.len(), ncells * npts * nbasis * pvs);
+
+    let mut j = [0.0; 9];
+    let mut k = [0.0; 9];
+    for (cell_geometry, cell_values) in geometries
+        .chunks(nnodes * gdim)
+        .zip(physical_values.chunks_mut(npts * nbasis * pvs))
+    {
+        for (p, point_values) in cell_values.chunks_mut(nbasis * pvs).enumerate() {
+            for a in 0..gdim {
+                for b in 0..tdim {
+                    j[a * tdim + b] = (0..nnodes)
+                        .map(|n| coordinate_table.get(1 + b, p, n, 0) * cell_geometry[n * gdim + a])
+                        .sum();
+                }
+            }
+            let det = jacobian_det_and_inverse(&j, gdim, tdim, &mut k);
+            for (i, values) in point_values.chunks_mut(pvs).enumerate() {
+                for (row, value) in values.iter_mut().enumerate() {
+                    *value = (0..value_size)
+                        .map(|col| {
+                            map_matrix_entry(map_type, &j, &k, det, gdim, tdim, row, col)
+                                * reference_values.get(0, p, i, col)
+                        })
+                        .sum();
+                }
             }
         }
-    } else {
-        unimplemented!("push_forward not yet implemented for this element");
     }
 }
 
@@ -480,4 +868,512 @@ mod test {
         assert_relative_eq!(*data.get(0, 0, 1, 0), 0.4);
         assert_relative_eq!(*data.get(0, 0, 2, 0), 0.3);
     }
+
+    #[test]
+    fn test_piola_3d() {
+        let e = create_raviart_thomas_element(ReferenceCellType::Tetrahedron, 1, false);
+        let mut data = TabulatedData::new(&e, 0, 1);
+        for i in 0..4 {
+            for v in 0..3 {
+                *data.get_mut(0, 0, i, v) = (i * 3 + v) as f64 * 0.1;
+            }
+        }
+
+        let coord_e = LagrangeElement::new(ReferenceCellType::Tetrahedron, 1);
+        let ref_cell = Tetrahedron {};
+        // J = [[1, 0, 1], [0, 2, 0], [0, 0, 1]] and det(J) = 2
+        let vertices = vec![1.0, 0.0, 0.0, 2.0, 0.0, 0.0, 1.0, 2.0, 0.0, 2.0, 0.0, 1.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 3);
+        let pts = vec![0.2, 0.3, 0.1];
+
+        contravariant_piola_push_forward(&mut data, &pts, &geometry);
+        for i in 0..4 {
+            let v = [0.3 * i as f64, 0.3 * i as f64 + 0.1, 0.3 * i as f64 + 0.2];
+            assert_relative_eq!(*data.get(0, 0, i, 0), (v[0] + v[2]) / 2.0, epsilon = 1e-14);
+            assert_relative_eq!(*data.get(0, 0, i, 1), v[1], epsilon = 1e-14);
+            assert_relative_eq!(*data.get(0, 0, i, 2), v[2] / 2.0, epsilon = 1e-14);
+        }
+        contravariant_piola_pull_back(&mut data, &pts, &geometry);
+        covariant_piola_push_forward(&mut data, &pts, &geometry);
+        for i in 0..4 {
+            let v = [0.3 * i as f64, 0.3 * i as f64 + 0.1, 0.3 * i as f64 + 0.2];
+            assert_relative_eq!(*data.get(0, 0, i, 0), v[0], epsilon = 1e-14);
+            assert_relative_eq!(*data.get(0, 0, i, 1), v[1] / 2.0, epsilon = 1e-14);
+            assert_relative_eq!(*data.get(0, 0, i, 2), v[2] - v[0], epsilon = 1e-14);
+        }
+        covariant_piola_pull_back(&mut data, &pts, &geometry);
+        for i in 0..4 {
+            for v in 0..3 {
+                assert_relative_eq!(
+                    *data.get(0, 0, i, v),
+                    (i * 3 + v) as f64 * 0.1,
+                    epsilon = 1e-14
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_piola_manifold() {
+        let e = RaviartThomasElementTriangleDegree1 {};
+        let mut data = TabulatedData::new(&e, 0, 1);
+        *data.get_mut(0, 0, 0, 0) = 0.5;
+        *data.get_mut(0, 0, 0, 1) = 0.4;
+        *data.get_mut(0, 0, 1, 0) = 0.3;
+        *data.get_mut(0, 0, 1, 1) = 0.2;
+        *data.get_mut(0, 0, 2, 0) = 0.1;
+        *data.get_mut(0, 0, 2, 1) = 0.0;
+
+        // A triangle in the plane x + y + z = 1
+        let coord_e = LagrangeElementTriangleDegree1 {};
+        let ref_cell = Triangle {};
+        let vertices = vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 3);
+        let pts = vec![0.3, 0.3];
+
+        for push_forward in [
+            contravariant_piola_push_forward,
+            covariant_piola_push_forward,
+        ] {
+            let mut mapped = TabulatedData::new(&e, 0, 1);
+            for i in 0..3 {
+                for v in 0..2 {
+                    *mapped.get_mut(0, 0, i, v) = *data.get(0, 0, i, v);
+                }
+            }
+            push_forward(&mut mapped, &pts, &geometry);
+            assert_eq!(mapped.value_size(), 3);
+            for i in 0..3 {
+                // The mapped vectors are tangent to the surface
+                let normal_component = (0..3).map(|v| mapped.get(0, 0, i, v)).sum::<f64>();
+                assert_relative_eq!(normal_component, 0.0, epsilon = 1e-14);
+            }
+        }
+
+        let mut mapped = TabulatedData::new(&e, 0, 1);
+        *mapped.get_mut(0, 0, 0, 0) = 0.5;
+        *mapped.get_mut(0, 0, 0, 1) = 0.4;
+        contravariant_piola_push_forward(&mut mapped, &pts, &geometry);
+        // J = [[-1, -1], [1, 0], [0, 1]] and sqrt(det(J^T J)) = sqrt(3)
+        assert_relative_eq!(*mapped.get(0, 0, 0, 0), -0.9 / 3f64.sqrt(), epsilon = 1e-14);
+        assert_relative_eq!(*mapped.get(0, 0, 0, 1), 0.5 / 3f64.sqrt(), epsilon = 1e-14);
+        assert_relative_eq!(*mapped.get(0, 0, 0, 2), 0.4 / 3f64.sqrt(), epsilon = 1e-14);
+        contravariant_piola_pull_back(&mut mapped, &pts, &geometry);
+        assert_eq!(mapped.value_size(), 2);
+        assert_relative_eq!(*mapped.get(0, 0, 0, 0), 0.5, epsilon = 1e-14);
+        assert_relative_eq!(*mapped.get(0, 0, 0, 1), 0.4, epsilon = 1e-14);
+
+        let e = LagrangeElementTriangleDegree1 {};
+        let mut data = TabulatedData::new(&e, 0, 1);
+        *data.get_mut(0, 0, 0, 0) = 0.5;
+        l2_piola_push_forward(&mut data, &pts, &geometry);
+        assert_relative_eq!(*data.get(0, 0, 0, 0), 0.5 * 3f64.sqrt(), epsilon = 1e-14);
+        l2_piola_pull_back(&mut data, &pts, &geometry);
+        assert_relative_eq!(*data.get(0, 0, 0, 0), 0.5, epsilon = 1e-14);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Derivatives can only be pushed forward on cells where tdim equals gdim"
+    )]
+    fn test_push_forward_derivatives_manifold() {
+        // A triangle in the plane x + y + z = 1
+        let coord_e = LagrangeElementTriangleDegree1 {};
+        let ref_cell = Triangle {};
+        let vertices = vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 3);
+        let pts = vec![0.3, 0.3];
+
+        let e = create_raviart_thomas_element(ReferenceCellType::Triangle, 1, false);
+        let mut reference_values = TabulatedData::new(&e, 1, 1);
+        e.tabulate(&pts, 1, &mut reference_values);
+        let mut physical_values = TabulatedData::new(&e, 1, 1);
+        push_forward(&e, &reference_values, &pts, &geometry, &mut physical_values);
+    }
+
+    #[test]
+    fn test_piola_interval() {
+        let e = LagrangeElementIntervalDegree1 {};
+        let mut data = TabulatedData::new(&e, 0, 1);
+        *data.get_mut(0, 0, 0, 0) = 0.5;
+        *data.get_mut(0, 0, 1, 0) = 0.4;
+
+        // An interval in 2D with J = [3, 4]
+        let coord_e = LagrangeElementIntervalDegree1 {};
+        let ref_cell = Interval {};
+        let vertices = vec![1.0, 1.0, 4.0, 5.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        let pts = vec![0.4];
+
+        covariant_piola_push_forward(&mut data, &pts, &geometry);
+        assert_relative_eq!(*data.get(0, 0, 0, 0), 0.06, epsilon = 1e-14);
+        assert_relative_eq!(*data.get(0, 0, 0, 1), 0.08, epsilon = 1e-14);
+        covariant_piola_pull_back(&mut data, &pts, &geometry);
+        assert_relative_eq!(*data.get(0, 0, 0, 0), 0.5, epsilon = 1e-14);
+        assert_relative_eq!(*data.get(0, 0, 1, 0), 0.4, epsilon = 1e-14);
+
+        contravariant_piola_push_forward(&mut data, &pts, &geometry);
+        assert_relative_eq!(*data.get(0, 0, 0, 0), 0.3, epsilon = 1e-14);
+        assert_relative_eq!(*data.get(0, 0, 0, 1), 0.4, epsilon = 1e-14);
+        contravariant_piola_pull_back(&mut data, &pts, &geometry);
+        assert_relative_eq!(*data.get(0, 0, 0, 0), 0.5, epsilon = 1e-14);
+
+        l2_piola_push_forward(&mut data, &pts, &geometry);
+        assert_relative_eq!(*data.get(0, 0, 0, 0), 2.5, epsilon = 1e-14);
+    }
+
+    /// A degree 0 element whose values are 2 by 2 matrices
+    fn matrix_element() -> CiarletElement {
+        let mut identity = vec![0.0; 16];
+        for i in 0..4 {
+            identity[i * 5] = 1.0;
+        }
+        CiarletElement::create(
+            ElementFamily::Lagrange,
+            ReferenceCellType::Triangle,
+            0,
+            vec![2, 2],
+            &identity,
+            [
+                vec![vec![], vec![], vec![]],
+                vec![vec![], vec![], vec![]],
+                vec![vec![1.0 / 3.0, 1.0 / 3.0]],
+                vec![],
+            ],
+            [
+                vec![vec![], vec![], vec![]],
+                vec![vec![], vec![], vec![]],
+                vec![identity.clone()],
+                vec![],
+            ],
+            MapType::Identity,
+            true,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_double_piola() {
+        let e = matrix_element();
+        let coord_e = LagrangeElementTriangleDegree1 {};
+        let ref_cell = Triangle {};
+        let vertices = vec![0.0, 1.0, 1.0, 0.0, 2.0, 1.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        let pts = vec![0.3, 0.3];
+
+        // J = [[1, 2], [-1, 0]], det(J) = 2 and J^{-1} = [[0, -1], [0.5, 0.5]]
+        let mut data = TabulatedData::new(&e, 0, 1);
+        *data.get_mut(0, 0, 0, 0) = 1.0;
+        *data.get_mut(0, 0, 1, 1) = 1.0;
+        double_covariant_piola_push_forward(&mut data, &pts, &geometry);
+        for (v, value) in [0.0, 0.0, 0.0, 1.0].iter().enumerate() {
+            assert_relative_eq!(*data.get(0, 0, 0, v), value, epsilon = 1e-14);
+        }
+        for (v, value) in [0.0, 0.0, -0.5, -0.5].iter().enumerate() {
+            assert_relative_eq!(*data.get(0, 0, 1, v), value, epsilon = 1e-14);
+        }
+        double_covariant_piola_pull_back(&mut data, &pts, &geometry);
+        for (v, value) in [1.0, 0.0, 0.0, 0.0].iter().enumerate() {
+            assert_relative_eq!(*data.get(0, 0, 0, v), value, epsilon = 1e-14);
+        }
+        for (v, value) in [0.0, 1.0, 0.0, 0.0].iter().enumerate() {
+            assert_relative_eq!(*data.get(0, 0, 1, v), value, epsilon = 1e-14);
+        }
+
+        double_contravariant_piola_push_forward(&mut data, &pts, &geometry);
+        for (v, value) in [0.25, -0.25, -0.25, 0.25].iter().enumerate() {
+            assert_relative_eq!(*data.get(0, 0, 0, v), value, epsilon = 1e-14);
+        }
+        for (v, value) in [0.5, 0.0, -0.5, 0.0].iter().enumerate() {
+            assert_relative_eq!(*data.get(0, 0, 1, v), value, epsilon = 1e-14);
+        }
+        double_contravariant_piola_pull_back(&mut data, &pts, &geometry);
+        for (v, value) in [1.0, 0.0, 0.0, 0.0].iter().enumerate() {
+            assert_relative_eq!(*data.get(0, 0, 0, v), value, epsilon = 1e-14);
+        }
+        for (v, value) in [0.0, 1.0, 0.0, 0.0].iter().enumerate() {
+            assert_relative_eq!(*data.get(0, 0, 1, v), value, epsilon = 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_double_piola_manifold() {
+        let e = matrix_element();
+        let coord_e = LagrangeElementTriangleDegree1 {};
+        let ref_cell = Triangle {};
+        let vertices = vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 3);
+        let pts = vec![0.3, 0.3];
+
+        for (push_forward, pull_back) in [
+            (
+                double_covariant_piola_push_forward as fn(&mut _, &_, &_),
+                double_covariant_piola_pull_back as fn(&mut _, &_, &_),
+            ),
+            (
+                double_contravariant_piola_push_forward,
+                double_contravariant_piola_pull_back,
+            ),
+        ] {
+            let mut data = TabulatedData::new(&e, 0, 1);
+            for i in 0..4 {
+                for v in 0..4 {
+                    *data.get_mut(0, 0, i, v) = (i * 4 + v) as f64;
+                }
+            }
+            push_forward(&mut data, &pts, &geometry);
+            assert_eq!(data.value_size(), 9);
+            pull_back(&mut data, &pts, &geometry);
+            assert_eq!(data.value_size(), 4);
+            for i in 0..4 {
+                for v in 0..4 {
+                    assert_relative_eq!(*data.get(0, 0, i, v), (i * 4 + v) as f64, epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    /// Check the derivatives pushed forward by `push_forward` against finite differences of the
+    /// pushed forward values
+    fn check_pushed_derivatives<F: FiniteElement, C: ReferenceCell>(
+        e: &F,
+        geometry: &PhysicalCell<LagrangeElement, C>,
+        point: &[f64],
+        push_forward: fn(&mut TabulatedData, &[f64], &PhysicalCell<LagrangeElement, C>),
+    ) {
+        let tdim = geometry.tdim();
+        let mut data = TabulatedData::new(e, 2, 1);
+        e.tabulate(point, 2, &mut data);
+        push_forward(&mut data, point, geometry);
+        let value_size = data.value_size();
+
+        let x = geometry.push_forward_points(point);
+        let h = 1e-3;
+        let values_at = |offsets: &[(usize, f64)]| {
+            let mut y = x.clone();
+            for (i, o) in offsets {
+                y[*i] += o;
+            }
+            let pt = geometry.pull_back_points(&y).unwrap();
+            let mut values = TabulatedData::new(e, 0, 1);
+            e.tabulate(&pt, 0, &mut values);
+            push_forward(&mut values, &pt, geometry);
+            values
+        };
+        for i in 0..tdim {
+            let mut orders = [0; 3];
+            orders[i] += 1;
+            let plus = values_at(&[(i, h)]);
+            let minus = values_at(&[(i, -h)]);
+            for fun in 0..e.dim() {
+                for v in 0..value_size {
+                    assert_relative_eq!(
+                        *data.get(derivative_index(tdim, &orders), 0, fun, v),
+                        (plus.get(0, 0, fun, v) - minus.get(0, 0, fun, v)) / (2.0 * h),
+                        epsilon = 1e-5
+                    );
+                }
+            }
+            for j in 0..tdim {
+                orders[j] += 1;
+                let pp = values_at(&[(i, h), (j, h)]);
+                let pm = values_at(&[(i, h), (j, -h)]);
+                let mp = values_at(&[(i, -h), (j, h)]);
+                let mm = values_at(&[(i, -h), (j, -h)]);
+                for fun in 0..e.dim() {
+                    for v in 0..value_size {
+                        assert_relative_eq!(
+                            *data.get(derivative_index(tdim, &orders), 0, fun, v),
+                            (pp.get(0, 0, fun, v) - pm.get(0, 0, fun, v) - mp.get(0, 0, fun, v)
+                                + mm.get(0, 0, fun, v))
+                                / (4.0 * h * h),
+                            epsilon = 1e-5
+                        );
+                    }
+                }
+                orders[j] -= 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_push_forward_derivatives() {
+        // A bilinear quadrilateral
+        let coord_e = LagrangeElement::new(ReferenceCellType::Quadrilateral, 1);
+        let ref_cell = Quadrilateral {};
+        let vertices = vec![0.0, 0.0, 2.0, 0.0, 0.0, 1.0, 3.0, 2.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        let pt = vec![0.4, 0.3];
+
+        let e = create_lagrange_element(ReferenceCellType::Quadrilateral, 2, false);
+        check_pushed_derivatives(&e, &geometry, &pt, identity_push_forward);
+        check_pushed_derivatives(&e, &geometry, &pt, l2_piola_push_forward);
+        let e = create_rtc_element(ReferenceCellType::Quadrilateral, 2, false);
+        check_pushed_derivatives(&e, &geometry, &pt, contravariant_piola_push_forward);
+        let e = create_nce_element(ReferenceCellType::Quadrilateral, 2, false);
+        check_pushed_derivatives(&e, &geometry, &pt, covariant_piola_push_forward);
+
+        // A P2 triangle with a curved edge
+        let coord_e = LagrangeElement::new(ReferenceCellType::Triangle, 2);
+        let ref_cell = Triangle {};
+        let vertices = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.6, 0.6, 0.0, 0.5, 0.5, 0.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        let pt = vec![0.2, 0.3];
+        let e = create_regge_element(ReferenceCellType::Triangle, 1, false);
+        check_pushed_derivatives(&e, &geometry, &pt, double_covariant_piola_push_forward);
+        let e = create_hhj_element(ReferenceCellType::Triangle, 1, false);
+        check_pushed_derivatives(&e, &geometry, &pt, double_contravariant_piola_push_forward);
+    }
+
+    #[test]
+    fn test_push_forward_derivatives_3d() {
+        // A trilinear hexahedron
+        let coord_e = LagrangeElement::new(ReferenceCellType::Hexahedron, 1);
+        let ref_cell = Hexahedron {};
+        let vertices = vec![
+            0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.2, 1.1, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0,
+            1.3, 0.1, 1.0, 1.0, 1.0, 1.0, 1.0,
+        ];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 3);
+        let pt = vec![0.4, 0.3, 0.6];
+
+        let e = create_lagrange_element(ReferenceCellType::Hexahedron, 1, false);
+        check_pushed_derivatives(&e, &geometry, &pt, identity_push_forward);
+        let e = create_nce_element(ReferenceCellType::Hexahedron, 1, false);
+        check_pushed_derivatives(&e, &geometry, &pt, covariant_piola_push_forward);
+        let e = create_rtc_element(ReferenceCellType::Hexahedron, 1, false);
+        check_pushed_derivatives(&e, &geometry, &pt, contravariant_piola_push_forward);
+    }
+
+    #[test]
+    fn test_push_forward_dispatch() {
+        let e = create_element(
+            ElementFamily::RaviartThomas,
+            ReferenceCellType::Triangle,
+            2,
+            Continuity::Continuous,
+        )
+        .unwrap();
+        let coord_e = LagrangeElementTriangleDegree1 {};
+        let ref_cell = Triangle {};
+        let pts = vec![0.3, 0.3, 0.1, 0.2];
+        let mut reference_values = TabulatedData::new(e.as_ref(), 1, 2);
+        e.tabulate(&pts, 1, &mut reference_values);
+        let mut physical_values = TabulatedData::new(e.as_ref(), 1, 2);
+
+        // The same reference values are pushed forward to two cells
+        for vertices in [
+            vec![0.0, 1.0, 1.0, 0.0, 2.0, 1.0],
+            vec![0.0, 0.0, 2.0, 0.0, 0.5, 3.0],
+        ] {
+            let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+            push_forward(
+                e.as_ref(),
+                &reference_values,
+                &pts,
+                &geometry,
+                &mut physical_values,
+            );
+
+            let mut expected = TabulatedData::new(e.as_ref(), 1, 2);
+            e.tabulate(&pts, 1, &mut expected);
+            contravariant_piola_push_forward(&mut expected, &pts, &geometry);
+            assert_eq!(physical_values.deriv_count(), 3);
+            for (a, b) in physical_values.as_slice().iter().zip(expected.as_slice()) {
+                assert_relative_eq!(a, b, epsilon = 1e-14);
+            }
+        }
+
+        let mut values = TabulatedData::new(e.as_ref(), 0, 2);
+        e.tabulate(&pts, 0, &mut values);
+        let vertices = vec![0.0, 0.0, 2.0, 0.0, 0.5, 3.0];
+        let geometry = PhysicalCell::new(&ref_cell, &vertices, &coord_e, 2);
+        push_forward(e.as_ref(), &values, &pts, &geometry, &mut physical_values);
+        let mut pulled_back = TabulatedData::new(e.as_ref(), 0, 2);
+        pull_back(
+            e.as_ref(),
+            &physical_values,
+            &pts,
+            &geometry,
+            &mut pulled_back,
+        );
+        for (a, b) in pulled_back.as_slice().iter().zip(values.as_slice()) {
+            assert_relative_eq!(a, b, epsilon = 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_push_forward_batch() {
+        let coord_e = LagrangeElementTriangleDegree1 {};
+        let ref_cell = Triangle {};
+        let pts = vec![0.3, 0.3, 0.1, 0.2, 0.6, 0.1];
+        let mut coordinate_table = TabulatedData::new(&coord_e, 1, 3);
+        coord_e.tabulate(&pts, 1, &mut coordinate_table);
+
+        for (gdim, geometries) in [
+            (
+                2,
+                vec![
+                    0.0, 1.0, 1.0, 0.0, 2.0, 1.0, 0.0, 0.0, 2.0, 0.0, 0.5, 3.0, 1.0, 1.0, 0.0, 2.0,
+                    -1.0, 0.5,
+                ],
+            ),
+            (
+                3,
+                vec![
+                    1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 1.0, 0.5,
+                    3.0, 0.0,
+                ],
+            ),
+        ] {
+            for family in [
+                ElementFamily::Lagrange,
+                ElementFamily::RaviartThomas,
+                ElementFamily::NedelecFirstKind,
+                ElementFamily::Regge,
+                ElementFamily::HellanHerrmannJohnson,
+            ] {
+                let e = create_element(
+                    family,
+                    ReferenceCellType::Triangle,
+                    1,
+                    Continuity::Continuous,
+                )
+                .unwrap();
+                let mut reference_values = TabulatedData::new(e.as_ref(), 0, 3);
+                e.tabulate(&pts, 0, &mut reference_values);
+                let pvs = physical_value_size(e.map_type(), e.value_size(), gdim);
+                let ncells = geometries.len() / (3 * gdim);
+                let mut physical_values = vec![0.0; ncells * 3 * e.dim() * pvs];
+                push_forward_batch(
+                    e.as_ref(),
+                    &reference_values,
+                    &coordinate_table,
+                    gdim,
+                    &geometries,
+                    &mut physical_values,
+                );
+
+                let mut expected = TabulatedData::new(e.as_ref(), 0, 3);
+                for (cell, vertices) in geometries.chunks(3 * gdim).enumerate() {
+                    let geometry = PhysicalCell::new(&ref_cell, vertices, &coord_e, gdim);
+                    push_forward(
+                        e.as_ref(),
+                        &reference_values,
+                        &pts,
+                        &geometry,
+                        &mut expected,
+                    );
+                    assert_eq!(expected.value_size(), pvs);
+                    let n = expected.as_slice().len();
+                    for (a, b) in physical_values[cell * n..(cell + 1) * n]
+                        .iter()
+                        .zip(expected.as_slice())
+                    {
+                        assert_relative_eq!(a, b, epsilon = 1e-12);
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,646 @@
+//! Orthonormal polynomial sets
+//!
+//! The functions in this module tabulate orthonormal bases of the polynomial spaces on each
+//! reference cell: Legendre polynomials on the interval, quadrilateral and hexahedron, Dubiner
+//! polynomials on the triangle and tetrahedron, products of Dubiner and Legendre polynomials on
+//! the prism, and the rational basis of Bergot, Cohen and Duruflé on the pyramid.
+//!
+//! The polynomials are orthonormal with respect to the L2 inner product on the reference cell.
+//! Data is tabulated using the same `(deriv, point, basis)` layout as [crate::element::TabulatedData].
+
+use crate::cell::*;
+
+/// The number of derivatives of order up to `nderivs` of a function on a cell
+pub fn derivative_count(cell_type: ReferenceCellType, nderivs: usize) -> usize {
+    match cell_type {
+        ReferenceCellType::Interval => nderivs + 1,
+        ReferenceCellType::Triangle => (nderivs + 1) * (nderivs + 2) / 2,
+        ReferenceCellType::Quadrilateral => (nderivs + 1) * (nderivs + 2) / 2,
+        ReferenceCellType::Tetrahedron => (nderivs + 1) * (nderivs + 2) * (nderivs + 3) / 6,
+        ReferenceCellType::Hexahedron => (nderivs + 1) * (nderivs + 2) * (nderivs + 3) / 6,
+        ReferenceCellType::Prism => (nderivs + 1) * (nderivs + 2) * (nderivs + 3) / 6,
+        ReferenceCellType::Pyramid => (nderivs + 1) * (nderivs + 2) * (nderivs + 3) / 6,
+    }
+}
+
+/// The index of a derivative in tabulated data
+///
+/// `orders` gives the number of times the function is differentiated with respect to each
+/// coordinate. The derivatives are sorted by total order; derivatives of the same order are
+/// sorted so that the last coordinate varies fastest. For example in 2D, the order is
+/// (0, 0), (1, 0), (0, 1), (2, 0), (1, 1), (0, 2), ...
+pub fn derivative_index(tdim: usize, orders: &[usize; 3]) -> usize {
+    match tdim {
+        1 => orders[0],
+        2 => {
+            let n = orders[0] + orders[1];
+            n * (n + 1) / 2 + orders[1]
+        }
+        3 => {
+            let n = orders[0] + orders[1] + orders[2];
+            let m = orders[1] + orders[2];
+            n * (n + 1) * (n + 2) / 6 + m * (m + 1) / 2 + orders[2]
+        }
+        _ => panic!("Unsupported dimension: {tdim}"),
+    }
+}
+
+/// The derivative orders of every derivative of order up to `nderivs`, sorted by [derivative_index]
+pub fn derivative_orders(tdim: usize, nderivs: usize) -> Vec<[usize; 3]> {
+    let mut orders = vec![];
+    for n in 0..nderivs + 1 {
+        match tdim {
+            1 => orders.push([n, 0, 0]),
+            2 => {
+                for q in 0..n + 1 {
+                    orders.push([n - q, q, 0]);
+                }
+            }
+            3 => {
+                for m in 0..n + 1 {
+                    for r in 0..m + 1 {
+                        orders.push([n - m, m - r, r]);
+                    }
+                }
+            }
+            _ => panic!("Unsupported dimension: {tdim}"),
+        }
+    }
+    orders
+}
+
+/// The number of polynomials in the orthonormal set of degree `degree` on a cell
+pub fn polyset_dim(cell_type: ReferenceCellType, degree: usize) -> usize {
+    let k = degree;
+    match cell_type {
+        ReferenceCellType::Interval => k + 1,
+        ReferenceCellType::Triangle => (k + 1) * (k + 2) / 2,
+        ReferenceCellType::Quadrilateral => (k + 1) * (k + 1),
+        ReferenceCellType::Tetrahedron => (k + 1) * (k + 2) * (k + 3) / 6,
+        ReferenceCellType::Hexahedron => (k + 1) * (k + 1) * (k + 1),
+        ReferenceCellType::Prism => (k + 1) * (k + 1) * (k + 2) / 2,
+        ReferenceCellType::Pyramid => (k + 1) * (k + 2) * (2 * k + 3) / 6,
+    }
+}
+
+/// Tabulate the orthonormal polynomials of degree `degree` on a cell and their derivatives up to order `nderivs`
+///
+/// `points` contains the coordinates of the points, with the first tdim components being the
+/// first point, the next tdim the second point, and so on. The value of derivative `d` of
+/// polynomial `b` at point `p` is written to `data[(d * npoints + p) * polyset_dim + b]`.
+///
+/// The polynomials are sorted so that the polynomials of degree less than `degree` come first,
+/// except on the quadrilateral, hexahedron and prism, which use a tensor product ordering. On the
+/// quadrilateral and hexahedron, the product of Legendre polynomials of degrees `i`, `j` (and `k`)
+/// has index `i * (degree + 1) + j` (or `(i * (degree + 1) + j) * (degree + 1) + k`). On the
+/// prism, the product of Dubiner polynomial `t` on the triangle and the Legendre polynomial of
+/// degree `z` in the third coordinate has index `t * (degree + 1) + z`.
+pub fn tabulate_legendre_polynomials(
+    cell_type: ReferenceCellType,
+    points: &[f64],
+    degree: usize,
+    nderivs: usize,
+    data: &mut [f64],
+) {
+    let tdim = create_cell(cell_type).dim();
+    let npts = points.len() / tdim;
+    let nderiv_count = derivative_count(cell_type, nderivs);
+    let dim = polyset_dim(cell_type, degree);
+    assert_eq!(points.len(), npts * tdim);
+    assert_eq!(data.len(), nderiv_count * npts * dim);
+
+    let orders = derivative_orders(tdim, nderivs);
+    for p in 0..npts {
+        let point = &points[p * tdim..(p + 1) * tdim];
+        let values = match cell_type {
+            ReferenceCellType::Interval => tabulate_interval(point, degree, &orders),
+            ReferenceCellType::Triangle => tabulate_triangle(point, degree, &orders),
+            ReferenceCellType::Quadrilateral => tabulate_quadrilateral(point, degree, &orders),
+            ReferenceCellType::Tetrahedron => tabulate_tetrahedron(point, degree, &orders),
+            ReferenceCellType::Hexahedron => tabulate_hexahedron(point, degree, &orders),
+            ReferenceCellType::Prism => tabulate_prism(point, degree, &orders),
+            ReferenceCellType::Pyramid => tabulate_pyramid(point, degree, &orders),
+        };
+        for (b, v) in values.iter().enumerate() {
+            for d in 0..nderiv_count {
+                data[(d * npts + p) * dim + b] = v[d];
+            }
+        }
+    }
+}
+
+/// A linear function `constant + coefficients . x`
+struct Linear {
+    constant: f64,
+    coefficients: [f64; 3],
+}
+
+impl Linear {
+    fn new(constant: f64, coefficients: [f64; 3]) -> Self {
+        Self {
+            constant,
+            coefficients,
+        }
+    }
+    fn eval(&self, point: &[f64]) -> f64 {
+        self.constant
+            + point
+                .iter()
+                .zip(self.coefficients.iter())
+                .map(|(x, c)| x * c)
+                .sum::<f64>()
+    }
+}
+
+/// Compute the derivatives of `l * f - c * m^2 * g` at a point
+///
+/// `f` and `g` contain the derivatives of two functions at the point, sorted by [derivative_index].
+/// `l` and `m` are linear functions.
+fn recurrence(
+    orders: &[[usize; 3]],
+    point: &[f64],
+    l: &Linear,
+    f: &[f64],
+    c: f64,
+    m: &Linear,
+    g: Option<&[f64]>,
+) -> Vec<f64> {
+    let tdim = point.len();
+    let l_value = l.eval(point);
+    let m_value = m.eval(point);
+    let mut result = vec![0.0; orders.len()];
+    for (index, o) in orders.iter().enumerate() {
+        let mut value = l_value * f[index];
+        for i in 0..tdim {
+            if o[i] > 0 {
+                let mut lower = *o;
+                lower[i] -= 1;
+                value += o[i] as f64 * l.coefficients[i] * f[derivative_index(tdim, &lower)];
+            }
+        }
+        if let Some(g) = g {
+            let mut g_part = m_value * m_value * g[index];
+            for i in 0..tdim {
+                if o[i] > 0 {
+                    let mut lower = *o;
+                    lower[i] -= 1;
+                    g_part += 2.0
+                        * o[i] as f64
+                        * m_value
+                        * m.coefficients[i]
+                        * g[derivative_index(tdim, &lower)];
+                }
+                if o[i] > 1 {
+                    let mut lower = *o;
+                    lower[i] -= 2;
+                    g_part += (o[i] * (o[i] - 1)) as f64
+                        * m.coefficients[i]
+                        * m.coefficients[i]
+                        * g[derivative_index(tdim, &lower)];
+                }
+                for j in i + 1..tdim {
+                    if o[i] > 0 && o[j] > 0 {
+                        let mut lower = *o;
+                        lower[i] -= 1;
+                        lower[j] -= 1;
+                        g_part += 2.0
+                            * (o[i] * o[j]) as f64
+                            * m.coefficients[i]
+                            * m.coefficients[j]
+                            * g[derivative_index(tdim, &lower)];
+                    }
+                }
+            }
+            value -= c * g_part;
+        }
+        result[index] = value;
+    }
+    result
+}
+
+/// The derivatives of the constant function 1
+fn one(orders: &[[usize; 3]]) -> Vec<f64> {
+    let mut result = vec![0.0; orders.len()];
+    result[0] = 1.0;
+    result
+}
+
+/// The coefficients `(a1, a2, a3)` in the recurrence `P_n(x) = (a1 x + a2) P_{n-1}(x) - a3 P_{n-2}(x)`
+/// for the Jacobi polynomials `P^(alpha, 0)_n`
+///
+/// This requires `alpha` to be positive.
+fn jacobi_coefficients(alpha: usize, n: usize) -> (f64, f64, f64) {
+    let a = alpha as f64;
+    let n = n as f64;
+    let a1 = (2.0 * n + a - 1.0) * (2.0 * n + a) / (2.0 * n * (n + a));
+    let a2 = (2.0 * n + a - 1.0) * a * a / (2.0 * n * (n + a) * (2.0 * n + a - 2.0));
+    let a3 = (n + a - 1.0) * (n - 1.0) * (2.0 * n + a) / (n * (n + a) * (2.0 * n + a - 2.0));
+    (a1, a2, a3)
+}
+
+/// Tabulate the Legendre polynomials in the coordinate `axis` of a point
+///
+/// The polynomials are orthonormal on [0, 1].
+fn legendre(orders: &[[usize; 3]], point: &[f64], axis: usize, degree: usize) -> Vec<Vec<f64>> {
+    let mut values = vec![one(orders)];
+    for n in 1..degree + 1 {
+        let a = (2 * n - 1) as f64 / n as f64;
+        let mut coefficients = [0.0; 3];
+        coefficients[axis] = 2.0 * a;
+        let v = recurrence(
+            orders,
+            point,
+            &Linear::new(-a, coefficients),
+            &values[n - 1],
+            (n - 1) as f64 / n as f64,
+            &Linear::new(1.0, [0.0; 3]),
+            if n > 1 { Some(&values[n - 2]) } else { None },
+        );
+        values.push(v);
+    }
+    for (n, v) in values.iter_mut().enumerate() {
+        let scale = f64::sqrt((2 * n + 1) as f64);
+        for i in v.iter_mut() {
+            *i *= scale;
+        }
+    }
+    values
+}
+
+/// Tabulate the polynomials `(1 - y)^p P_p((2x - 1 + y) / (1 - y))`, where `P_p` is the degree `p`
+/// Legendre polynomial on [-1, 1]
+///
+/// Here, `x` is the coordinate `axis` of the point, and `y` is the sum of the coordinates in `collapsed`.
+fn collapsed_legendre(
+    orders: &[[usize; 3]],
+    point: &[f64],
+    axis: usize,
+    collapsed: &[usize],
+    degree: usize,
+) -> Vec<Vec<f64>> {
+    let mut values = vec![one(orders)];
+    for p in 1..degree + 1 {
+        let a = (2 * p - 1) as f64 / p as f64;
+        let mut l_coefficients = [0.0; 3];
+        let mut m_coefficients = [0.0; 3];
+        l_coefficients[axis] = 2.0 * a;
+        for i in collapsed {
+            l_coefficients[*i] = a;
+            m_coefficients[*i] = -1.0;
+        }
+        let v = recurrence(
+            orders,
+            point,
+            &Linear::new(-a, l_coefficients),
+            &values[p - 1],
+            (p - 1) as f64 / p as f64,
+            &Linear::new(1.0, m_coefficients),
+            if p > 1 { Some(&values[p - 2]) } else { None },
+        );
+        values.push(v);
+    }
+    values
+}
+
+/// Multiply every polynomial in a set by the polynomials `(1 - z)^q P^(alpha, 0)_q((2y - 1 + z) / (1 - z))`
+/// for `q` up to `degree`
+///
+/// Here, `y` is the coordinate `axis` of the point, and `z` is the coordinate `collapsed` (if
+/// present). If `collapsed` is `None`, the polynomials `P^(alpha, 0)_q(2y - 1)` are used.
+fn jacobi_extend(
+    orders: &[[usize; 3]],
+    point: &[f64],
+    f: &[f64],
+    alpha: usize,
+    axis: usize,
+    collapsed: Option<usize>,
+    degree: usize,
+) -> Vec<Vec<f64>> {
+    let mut values = vec![f.to_vec()];
+    for q in 1..degree + 1 {
+        let (a1, a2, a3) = jacobi_coefficients(alpha, q);
+        let mut l_coefficients = [0.0; 3];
+        let mut m_coefficients = [0.0; 3];
+        l_coefficients[axis] = 2.0 * a1;
+        if let Some(z) = collapsed {
+            l_coefficients[z] = a1 - a2;
+            m_coefficients[z] = -1.0;
+        }
+        let v = recurrence(
+            orders,
+            point,
+            &Linear::new(a2 - a1, l_coefficients),
+            &values[q - 1],
+            a3,
+            &Linear::new(1.0, m_coefficients),
+            if q > 1 { Some(&values[q - 2]) } else { None },
+        );
+        values.push(v);
+    }
+    values
+}
+
+fn scale(values: &mut [f64], factor: f64) {
+    for v in values.iter_mut() {
+        *v *= factor;
+    }
+}
+
+fn tabulate_interval(point: &[f64], degree: usize, orders: &[[usize; 3]]) -> Vec<Vec<f64>> {
+    legendre(orders, point, 0, degree)
+}
+
+fn tabulate_quadrilateral(point: &[f64], degree: usize, orders: &[[usize; 3]]) -> Vec<Vec<f64>> {
+    let px = legendre(orders, point, 0, degree);
+    let py = legendre(orders, point, 1, degree);
+    let mut values = vec![];
+    for fx in &px {
+        for fy in &py {
+            values.push(product(orders, point.len(), &[fx, fy]));
+        }
+    }
+    values
+}
+
+fn tabulate_hexahedron(point: &[f64], degree: usize, orders: &[[usize; 3]]) -> Vec<Vec<f64>> {
+    let px = legendre(orders, point, 0, degree);
+    let py = legendre(orders, point, 1, degree);
+    let pz = legendre(orders, point, 2, degree);
+    let mut values = vec![];
+    for fx in &px {
+        for fy in &py {
+            for fz in &pz {
+                values.push(product(orders, point.len(), &[fx, fy, fz]));
+            }
+        }
+    }
+    values
+}
+
+/// The index of the Dubiner polynomial of degree `p` in `x` and `q` in `y` on a triangle
+fn triangle_index(p: usize, q: usize) -> usize {
+    (p + q) * (p + q + 1) / 2 + q
+}
+
+/// The index of the Dubiner polynomial of degree `p` in `x`, `q` in `y` and `r` in `z` on a tetrahedron
+fn tetrahedron_index(p: usize, q: usize, r: usize) -> usize {
+    let n = p + q + r;
+    let m = q + r;
+    n * (n + 1) * (n + 2) / 6 + m * (m + 1) / 2 + r
+}
+
+fn tabulate_triangle(point: &[f64], degree: usize, orders: &[[usize; 3]]) -> Vec<Vec<f64>> {
+    let mut values = vec![vec![]; polyset_dim(ReferenceCellType::Triangle, degree)];
+    let fp = collapsed_legendre(orders, point, 0, &[1], degree);
+    for (p, f) in fp.iter().enumerate() {
+        let fq = jacobi_extend(orders, point, f, 2 * p + 1, 1, None, degree - p);
+        for (q, mut v) in fq.into_iter().enumerate() {
+            scale(&mut v, f64::sqrt((2 * (2 * p + 1) * (p + q + 1)) as f64));
+            values[triangle_index(p, q)] = v;
+        }
+    }
+    values
+}
+
+fn tabulate_tetrahedron(point: &[f64], degree: usize, orders: &[[usize; 3]]) -> Vec<Vec<f64>> {
+    let mut values = vec![vec![]; polyset_dim(ReferenceCellType::Tetrahedron, degree)];
+    let fp = collapsed_legendre(orders, point, 0, &[1, 2], degree);
+    for (p, f) in fp.iter().enumerate() {
+        let fq = jacobi_extend(orders, point, f, 2 * p + 1, 1, Some(2), degree - p);
+        for (q, g) in fq.iter().enumerate() {
+            let fr = jacobi_extend(orders, point, g, 2 * p + 2 * q + 2, 2, None, degree - p - q);
+            for (r, mut v) in fr.into_iter().enumerate() {
+                scale(
+                    &mut v,
+                    f64::sqrt((2 * (2 * p + 1) * (p + q + 1) * (2 * p + 2 * q + 2 * r + 3)) as f64),
+                );
+                values[tetrahedron_index(p, q, r)] = v;
+            }
+        }
+    }
+    values
+}
+
+fn tabulate_prism(point: &[f64], degree: usize, orders: &[[usize; 3]]) -> Vec<Vec<f64>> {
+    let triangle = tabulate_triangle(point, degree, orders);
+    let pz = legendre(orders, point, 2, degree);
+    let mut values = vec![];
+    for ft in &triangle {
+        for fz in &pz {
+            values.push(product(orders, point.len(), &[ft, fz]));
+        }
+    }
+    values
+}
+
+fn tabulate_pyramid(point: &[f64], degree: usize, orders: &[[usize; 3]]) -> Vec<Vec<f64>> {
+    let fx = collapsed_legendre(orders, point, 0, &[2], degree);
+    let fy = collapsed_legendre(orders, point, 1, &[2], degree);
+    let at_apex = (1.0 - point[2]).abs() < 1e-12;
+
+    let mut values = vec![];
+    for total in 0..degree + 1 {
+        for k in 0..total + 1 {
+            let m = total - k;
+            for (i, f) in fx.iter().enumerate().take(m + 1) {
+                for (j, g) in fy.iter().enumerate().take(m + 1) {
+                    if usize::max(i, j) != m {
+                        continue;
+                    }
+                    let s = usize::min(i, j);
+                    if s > 0 && at_apex {
+                        // The rational polynomials vanish at the apex of the pyramid
+                        values.push(vec![0.0; orders.len()]);
+                        continue;
+                    }
+                    // (1 - z)^-s
+                    let mut rational = vec![0.0; orders.len()];
+                    for (index, o) in orders.iter().enumerate() {
+                        if o[0] == 0 && o[1] == 0 {
+                            rational[index] = (0..o[2]).map(|n| (s + n) as f64).product::<f64>()
+                                * (1.0 - point[2]).powi(-((s + o[2]) as i32));
+                        }
+                    }
+                    let h = jacobi_extend(orders, point, &one(orders), 2 * m + 2, 2, None, k)
+                        .pop()
+                        .unwrap();
+                    let mut v = product(orders, 3, &[f, g, &rational, &h]);
+                    scale(
+                        &mut v,
+                        f64::sqrt(((2 * i + 1) * (2 * j + 1) * (2 * k + 2 * m + 3)) as f64),
+                    );
+                    values.push(v);
+                }
+            }
+        }
+    }
+    values
+}
+
+/// Compute the derivatives of a product of functions
+///
+/// Each function in `factors` is given as its derivatives at a point, sorted by [derivative_index].
+fn product(orders: &[[usize; 3]], tdim: usize, factors: &[&Vec<f64>]) -> Vec<f64> {
+    let mut result = factors[0].clone();
+    for f in &factors[1..] {
+        let mut new_result = vec![0.0; orders.len()];
+        for (index, o) in orders.iter().enumerate() {
+            for a in 0..o[0] + 1 {
+                for b in 0..o[1] + 1 {
+                    for c in 0..o[2] + 1 {
+                        let lower = [a, b, c];
+                        let rest = [o[0] - a, o[1] - b, o[2] - c];
+                        new_result[index] +=
+                            (binomial(o[0], a) * binomial(o[1], b) * binomial(o[2], c)) as f64
+                                * result[derivative_index(tdim, &lower)]
+                                * f[derivative_index(tdim, &rest)];
+                    }
+                }
+            }
+        }
+        result = new_result;
+    }
+    result
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    (0..k).fold(1, |b, i| b * (n - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::polyset::*;
+    use approx::*;
+
+    const CELL_TYPES: [ReferenceCellType; 7] = [
+        ReferenceCellType::Interval,
+        ReferenceCellType::Triangle,
+        ReferenceCellType::Quadrilateral,
+        ReferenceCellType::Tetrahedron,
+        ReferenceCellType::Hexahedron,
+        ReferenceCellType::Prism,
+        ReferenceCellType::Pyramid,
+    ];
+
+    /// A point in the interior of each reference cell
+    fn interior_point(cell_type: ReferenceCellType) -> Vec<f64> {
+        match cell_type {
+            ReferenceCellType::Interval => vec![0.3],
+            ReferenceCellType::Triangle => vec![0.3, 0.2],
+            ReferenceCellType::Quadrilateral => vec![0.3, 0.6],
+            ReferenceCellType::Tetrahedron => vec![0.3, 0.2, 0.1],
+            ReferenceCellType::Hexahedron => vec![0.3, 0.6, 0.8],
+            ReferenceCellType::Prism => vec![0.3, 0.2, 0.7],
+            ReferenceCellType::Pyramid => vec![0.3, 0.2, 0.4],
+        }
+    }
+
+    #[test]
+    fn test_derivative_index() {
+        for tdim in 1..4 {
+            for (i, o) in derivative_orders(tdim, 4).iter().enumerate() {
+                assert_eq!(derivative_index(tdim, o), i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_legendre_interval() {
+        let points = vec![0.0, 0.3, 0.8, 1.0];
+        let mut data = vec![0.0; 3 * 4 * 3];
+        tabulate_legendre_polynomials(ReferenceCellType::Interval, &points, 2, 2, &mut data);
+        for (p, x) in points.iter().enumerate() {
+            assert_relative_eq!(data[p * 3], 1.0, epsilon = 1e-12);
+            assert_relative_eq!(
+                data[p * 3 + 1],
+                f64::sqrt(3.0) * (2.0 * x - 1.0),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(
+                data[p * 3 + 2],
+                f64::sqrt(5.0) * (6.0 * x * x - 6.0 * x + 1.0),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(data[(4 + p) * 3 + 1], 2.0 * f64::sqrt(3.0), epsilon = 1e-12);
+            assert_relative_eq!(
+                data[(4 + p) * 3 + 2],
+                f64::sqrt(5.0) * (12.0 * x - 6.0),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(
+                data[(8 + p) * 3 + 2],
+                12.0 * f64::sqrt(5.0),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_legendre_triangle_degree_1() {
+        let points = vec![0.0, 0.0, 0.3, 0.2, 1.0, 0.0, 0.0, 1.0];
+        let mut data = vec![0.0; 4 * 3];
+        tabulate_legendre_polynomials(ReferenceCellType::Triangle, &points, 1, 0, &mut data);
+        for p in 0..4 {
+            let (x, y) = (points[2 * p], points[2 * p + 1]);
+            assert_relative_eq!(data[p * 3], f64::sqrt(2.0), epsilon = 1e-12);
+            assert_relative_eq!(
+                data[p * 3 + 1],
+                2.0 * f64::sqrt(3.0) * (2.0 * x + y - 1.0),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(data[p * 3 + 2], 2.0 * (3.0 * y - 1.0), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_legendre_constants() {
+        let volumes = [1.0, 0.5, 1.0, 1.0 / 6.0, 1.0, 0.5, 1.0 / 3.0];
+        for (cell_type, volume) in CELL_TYPES.iter().zip(volumes) {
+            let point = interior_point(*cell_type);
+            let mut data = vec![0.0; polyset_dim(*cell_type, 2)];
+            tabulate_legendre_polynomials(*cell_type, &point, 2, 0, &mut data);
+            assert_relative_eq!(data[0], 1.0 / f64::sqrt(volume), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_legendre_derivatives() {
+        let h = 1e-5;
+        for cell_type in CELL_TYPES {
+            for degree in 0..5 {
+                let point = interior_point(cell_type);
+                let tdim = point.len();
+                let dim = polyset_dim(cell_type, degree);
+                let nderiv_count = derivative_count(cell_type, 2);
+
+                let mut data = vec![0.0; nderiv_count * dim];
+                tabulate_legendre_polynomials(cell_type, &point, degree, 2, &mut data);
+
+                for d in 0..tdim {
+                    // Evaluate the first derivatives at points either side of the point
+                    let mut points = point.clone();
+                    points[d] -= h;
+                    points.extend(point.iter());
+                    points[tdim + d] += h;
+                    let mut fd_data = vec![0.0; (tdim + 1) * 2 * dim];
+                    tabulate_legendre_polynomials(cell_type, &points, degree, 1, &mut fd_data);
+
+                    for o in derivative_orders(tdim, 1) {
+                        let mut second = o;
+                        second[d] += 1;
+                        let i = derivative_index(tdim, &o);
+                        for b in 0..dim {
+                            assert_relative_eq!(
+                                data[derivative_index(tdim, &second) * dim + b],
+                                (fd_data[(2 * i + 1) * dim + b] - fd_data[2 * i * dim + b])
+                                    / (2.0 * h),
+                                epsilon = 1e-5,
+                                max_relative = 1e-5
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,451 @@
+//! Quadrature rules
+//!
+//! Gauss-Jacobi rules are defined on every reference cell: these are tensor products of
+//! Gauss-Legendre rules on the interval, quadrilateral and hexahedron, and collapsed rules on the
+//! other cells. Gauss-Lobatto rules are defined on the interval, quadrilateral and hexahedron.
+
+use crate::cell::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(u8)]
+pub enum QuadratureType {
+    GaussJacobi = 0,
+    GaussLobatto = 1,
+}
+
+/// A quadrature rule on a reference cell
+pub struct QuadratureRule {
+    /// The quadrature points
+    ///
+    /// The first dim components represent the first point, the next dim the second point, and so on.
+    pub points: Vec<f64>,
+    /// The quadrature weights
+    pub weights: Vec<f64>,
+    /// The number of points
+    pub npoints: usize,
+    /// The topological dimension of the cell
+    pub dim: usize,
+}
+
+/// Create a Gauss-Jacobi quadrature rule on a cell that is exact for polynomials of degree up to `degree`
+pub fn make_quadrature(cell_type: ReferenceCellType, degree: usize) -> QuadratureRule {
+    make_gauss_jacobi_quadrature(cell_type, (degree + 2) / 2)
+}
+
+/// Create a quadrature rule of the given type on a cell that is exact for polynomials of degree up to `degree`
+///
+/// An error is returned if the quadrature type is not defined on the cell.
+#[allow(clippy::result_unit_err)]
+pub fn make_quadrature_of_type(
+    quadrature_type: QuadratureType,
+    cell_type: ReferenceCellType,
+    degree: usize,
+) -> Result<QuadratureRule, ()> {
+    match quadrature_type {
+        QuadratureType::GaussJacobi => Ok(make_quadrature(cell_type, degree)),
+        QuadratureType::GaussLobatto => {
+            make_gauss_lobatto_quadrature(cell_type, usize::max(2, (degree + 4) / 2))
+        }
+    }
+}
+
+/// Create a Gauss-Jacobi quadrature rule on a cell with `m` points in each direction
+///
+/// On the interval, quadrilateral and hexahedron, this is a tensor product Gauss-Legendre rule.
+/// On the other cells, this is a collapsed rule with `m` points in each direction.
+pub fn make_gauss_jacobi_quadrature(cell_type: ReferenceCellType, m: usize) -> QuadratureRule {
+    let (x0, w0) = gauss_jacobi_rule(0, m);
+    let mut points = vec![];
+    let mut weights = vec![];
+    match cell_type {
+        ReferenceCellType::Interval => {
+            points = x0;
+            weights = w0;
+        }
+        ReferenceCellType::Quadrilateral => {
+            for i in 0..m {
+                for j in 0..m {
+                    points.extend([x0[i], x0[j]]);
+                    weights.push(w0[i] * w0[j]);
+                }
+            }
+        }
+        ReferenceCellType::Hexahedron => {
+            for i in 0..m {
+                for j in 0..m {
+                    for k in 0..m {
+                        points.extend([x0[i], x0[j], x0[k]]);
+                        weights.push(w0[i] * w0[j] * w0[k]);
+                    }
+                }
+            }
+        }
+        ReferenceCellType::Triangle => {
+            let (x1, w1) = gauss_jacobi_rule(1, m);
+            for i in 0..m {
+                for j in 0..m {
+                    points.extend([x0[i] * (1.0 - x1[j]), x1[j]]);
+                    weights.push(w0[i] * w1[j]);
+                }
+            }
+        }
+        ReferenceCellType::Tetrahedron => {
+            let (x1, w1) = gauss_jacobi_rule(1, m);
+            let (x2, w2) = gauss_jacobi_rule(2, m);
+            for i in 0..m {
+                for j in 0..m {
+                    for k in 0..m {
+                        let z = x2[k];
+                        let y = x1[j] * (1.0 - z);
+                        points.extend([x0[i] * (1.0 - y - z), y, z]);
+                        weights.push(w0[i] * w1[j] * w2[k]);
+                    }
+                }
+            }
+        }
+        ReferenceCellType::Prism => {
+            let (x1, w1) = gauss_jacobi_rule(1, m);
+            for i in 0..m {
+                for j in 0..m {
+                    for k in 0..m {
+                        points.extend([x0[i] * (1.0 - x1[j]), x1[j], x0[k]]);
+                        weights.push(w0[i] * w1[j] * w0[k]);
+                    }
+                }
+            }
+        }
+        ReferenceCellType::Pyramid => {
+            let (x2, w2) = gauss_jacobi_rule(2, m);
+            for i in 0..m {
+                for j in 0..m {
+                    for k in 0..m {
+                        let z = x2[k];
+                        points.extend([x0[i] * (1.0 - z), x0[j] * (1.0 - z), z]);
+                        weights.push(w0[i] * w0[j] * w2[k]);
+                    }
+                }
+            }
+        }
+    }
+    let dim = create_cell(cell_type).dim();
+    QuadratureRule {
+        npoints: weights.len(),
+        points,
+        weights,
+        dim,
+    }
+}
+
+/// Create a Gauss-Lobatto quadrature rule on a cell with `m` points in each direction
+///
+/// Gauss-Lobatto rules are only defined on the interval, quadrilateral and hexahedron with at
+/// least 2 points in each direction: an error is returned for other cells or if `m` is less than 2.
+#[allow(clippy::result_unit_err)]
+pub fn make_gauss_lobatto_quadrature(
+    cell_type: ReferenceCellType,
+    m: usize,
+) -> Result<QuadratureRule, ()> {
+    if m < 2 {
+        return Err(());
+    }
+    let (x, w) = gauss_lobatto_rule(m);
+    let mut points = vec![];
+    let mut weights = vec![];
+    match cell_type {
+        ReferenceCellType::Interval => {
+            points = x;
+            weights = w;
+        }
+        ReferenceCellType::Quadrilateral => {
+            for i in 0..m {
+                for j in 0..m {
+                    points.extend([x[i], x[j]]);
+                    weights.push(w[i] * w[j]);
+                }
+            }
+        }
+        ReferenceCellType::Hexahedron => {
+            for i in 0..m {
+                for j in 0..m {
+                    for k in 0..m {
+                        points.extend([x[i], x[j], x[k]]);
+                        weights.push(w[i] * w[j] * w[k]);
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(());
+        }
+    }
+    let dim = create_cell(cell_type).dim();
+    Ok(QuadratureRule {
+        npoints: weights.len(),
+        points,
+        weights,
+        dim,
+    })
+}
+
+/// Evaluate the Jacobi polynomial `P^(alpha, 0)_n` and its derivative at a point in [-1, 1]
+fn jacobi(alpha: usize, n: usize, x: f64) -> (f64, f64) {
+    let a = alpha as f64;
+    let mut p0 = 1.0;
+    let mut dp0 = 0.0;
+    if n == 0 {
+        return (p0, dp0);
+    }
+    let mut p1 = ((a + 2.0) * x + a) / 2.0;
+    let mut dp1 = (a + 2.0) / 2.0;
+    for k in 2..n + 1 {
+        let k = k as f64;
+        let a1 = (2.0 * k + a - 1.0) * (2.0 * k + a) / (2.0 * k * (k + a));
+        let a2 = (2.0 * k + a - 1.0) * a * a / (2.0 * k * (k + a) * (2.0 * k + a - 2.0));
+        let a3 = (k + a - 1.0) * (k - 1.0) * (2.0 * k + a) / (k * (k + a) * (2.0 * k + a - 2.0));
+        let p2 = (a1 * x + a2) * p1 - a3 * p0;
+        let dp2 = (a1 * x + a2) * dp1 + a1 * p1 - a3 * dp0;
+        p0 = p1;
+        p1 = p2;
+        dp0 = dp1;
+        dp1 = dp2;
+    }
+    (p1, dp1)
+}
+
+/// Compute the roots of `P^(alpha, 0)_m` using Newton's method with deflation
+fn jacobi_roots(alpha: usize, m: usize) -> Vec<f64> {
+    let mut roots: Vec<f64> = vec![];
+    for k in 0..m {
+        // Initial guess from the Chebyshev points, averaged with the previous root
+        let mut x = -f64::cos((2 * k + 1) as f64 * std::f64::consts::PI / (2 * m) as f64);
+        if k > 0 {
+            x = 0.5 * (x + roots[k - 1]);
+        }
+        for _ in 0..100 {
+            let (p, dp) = jacobi(alpha, m, x);
+            let s: f64 = roots.iter().map(|r| 1.0 / (x - r)).sum();
+            let delta = p / (dp - s * p);
+            x -= delta;
+            if delta.abs() < 1e-16 {
+                break;
+            }
+        }
+        roots.push(x);
+    }
+    roots
+}
+
+/// The points and weights of the `m`-point Gauss-Jacobi rule on [0, 1] for the weight `(1 - x)^alpha`
+pub fn gauss_jacobi_rule(alpha: usize, m: usize) -> (Vec<f64>, Vec<f64>) {
+    let roots = jacobi_roots(alpha, m);
+    let mut points = vec![];
+    let mut weights = vec![];
+    for x in roots {
+        let (_, dp) = jacobi(alpha, m, x);
+        points.push((1.0 + x) / 2.0);
+        // The weight on [-1, 1] is 2^(alpha + 1) / ((1 - x^2) P'(x)^2), and integrals on [0, 1]
+        // are smaller by a factor of 2^(alpha + 1)
+        weights.push(1.0 / ((1.0 - x * x) * dp * dp));
+    }
+    (points, weights)
+}
+
+/// The points and weights of the `m`-point Gauss-Lobatto rule on [0, 1]
+pub fn gauss_lobatto_rule(m: usize) -> (Vec<f64>, Vec<f64>) {
+    assert!(m >= 2);
+    // The interior points are the roots of P'_{m-1}, which is a multiple of P^(1, 1)_{m-2}; the
+    // roots are found using Newton's method with deflation, with P'' computed using the
+    // Legendre differential equation
+    let n = m - 1;
+    let legendre = |x: f64| -> (f64, f64) {
+        let mut p0 = 1.0;
+        let mut p1 = x;
+        let mut dp0 = 0.0;
+        let mut dp1 = 1.0;
+        for k in 2..n + 1 {
+            let k = k as f64;
+            let p2 = ((2.0 * k - 1.0) * x * p1 - (k - 1.0) * p0) / k;
+            let dp2 = ((2.0 * k - 1.0) * (x * dp1 + p1) - (k - 1.0) * dp0) / k;
+            p0 = p1;
+            p1 = p2;
+            dp0 = dp1;
+            dp1 = dp2;
+        }
+        (p1, dp1)
+    };
+    let mut roots: Vec<f64> = vec![];
+    for k in 1..n {
+        let mut x = -f64::cos(k as f64 * std::f64::consts::PI / n as f64);
+        for _ in 0..100 {
+            let (p, dp) = legendre(x);
+            let ddp = (2.0 * x * dp - (n * (n + 1)) as f64 * p) / (1.0 - x * x);
+            let s: f64 = roots.iter().map(|r| 1.0 / (x - r)).sum();
+            let delta = dp / (ddp - s * dp);
+            x -= delta;
+            if delta.abs() < 1e-16 {
+                break;
+            }
+        }
+        roots.push(x);
+    }
+
+    let mut points = vec![0.0];
+    let mut weights = vec![1.0 / (n * m) as f64];
+    for x in roots {
+        let (p, _) = legendre(x);
+        points.push((1.0 + x) / 2.0);
+        weights.push(1.0 / ((n * m) as f64 * p * p));
+    }
+    points.push(1.0);
+    weights.push(1.0 / (n * m) as f64);
+    (points, weights)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cell::*;
+    use crate::polyset::*;
+    use crate::quadrature::*;
+    use approx::*;
+
+    const CELL_TYPES: [ReferenceCellType; 7] = [
+        ReferenceCellType::Interval,
+        ReferenceCellType::Triangle,
+        ReferenceCellType::Quadrilateral,
+        ReferenceCellType::Tetrahedron,
+        ReferenceCellType::Hexahedron,
+        ReferenceCellType::Prism,
+        ReferenceCellType::Pyramid,
+    ];
+
+    fn factorial(n: usize) -> f64 {
+        (1..n + 1).map(|i| i as f64).product()
+    }
+
+    /// The integral of x^a y^b z^c over a reference cell
+    fn integral(cell_type: ReferenceCellType, a: usize, b: usize, c: usize) -> f64 {
+        match cell_type {
+            ReferenceCellType::Interval => 1.0 / (a + 1) as f64,
+            ReferenceCellType::Quadrilateral => 1.0 / ((a + 1) * (b + 1)) as f64,
+            ReferenceCellType::Hexahedron => 1.0 / ((a + 1) * (b + 1) * (c + 1)) as f64,
+            ReferenceCellType::Triangle => factorial(a) * factorial(b) / factorial(a + b + 2),
+            ReferenceCellType::Tetrahedron => {
+                factorial(a) * factorial(b) * factorial(c) / factorial(a + b + c + 3)
+            }
+            ReferenceCellType::Prism => {
+                factorial(a) * factorial(b) / factorial(a + b + 2) / (c + 1) as f64
+            }
+            ReferenceCellType::Pyramid => {
+                factorial(c) * factorial(a + b + 2)
+                    / factorial(a + b + c + 3)
+                    / ((a + 1) * (b + 1)) as f64
+            }
+        }
+    }
+
+    fn check_exactness(cell_type: ReferenceCellType, degree: usize, rule: &QuadratureRule) {
+        let dim = rule.dim;
+        for a in 0..degree + 1 {
+            for b in 0..if dim > 1 { degree + 1 - a } else { 1 } {
+                for c in 0..if dim > 2 { degree + 1 - a - b } else { 1 } {
+                    let mut result = 0.0;
+                    for (p, w) in rule.weights.iter().enumerate() {
+                        let mut value = *w;
+                        for (i, e) in [a, b, c].iter().enumerate().take(dim) {
+                            value *= rule.points[p * dim + i].powi(*e as i32);
+                        }
+                        result += value;
+                    }
+                    assert_relative_eq!(
+                        result,
+                        integral(cell_type, a, b, c),
+                        epsilon = 1e-14,
+                        max_relative = 1e-12
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gauss_jacobi_exactness() {
+        for cell_type in CELL_TYPES {
+            for degree in 0..9 {
+                let rule = make_quadrature(cell_type, degree);
+                assert_eq!(rule.dim, create_cell(cell_type).dim());
+                assert_eq!(rule.weights.len(), rule.npoints);
+                assert_eq!(rule.points.len(), rule.npoints * rule.dim);
+                check_exactness(cell_type, degree, &rule);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gauss_lobatto_exactness() {
+        for cell_type in [
+            ReferenceCellType::Interval,
+            ReferenceCellType::Quadrilateral,
+            ReferenceCellType::Hexahedron,
+        ] {
+            for degree in 0..9 {
+                let rule = make_quadrature_of_type(QuadratureType::GaussLobatto, cell_type, degree)
+                    .unwrap();
+                check_exactness(cell_type, degree, &rule);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gauss_lobatto_unsupported() {
+        for cell_type in [
+            ReferenceCellType::Triangle,
+            ReferenceCellType::Tetrahedron,
+            ReferenceCellType::Prism,
+            ReferenceCellType::Pyramid,
+        ] {
+            assert!(make_gauss_lobatto_quadrature(cell_type, 3).is_err());
+            assert!(make_quadrature_of_type(QuadratureType::GaussLobatto, cell_type, 2).is_err());
+        }
+        assert!(make_gauss_lobatto_quadrature(ReferenceCellType::Interval, 1).is_err());
+    }
+
+    #[test]
+    fn test_gauss_lobatto_interval() {
+        let (points, weights) = gauss_lobatto_rule(4);
+        assert_relative_eq!(points[0], 0.0);
+        assert_relative_eq!(points[1], 0.5 - f64::sqrt(5.0) / 10.0, epsilon = 1e-14);
+        assert_relative_eq!(points[2], 0.5 + f64::sqrt(5.0) / 10.0, epsilon = 1e-14);
+        assert_relative_eq!(points[3], 1.0);
+        assert_relative_eq!(weights[0], 1.0 / 12.0, epsilon = 1e-14);
+        assert_relative_eq!(weights[1], 5.0 / 12.0, epsilon = 1e-14);
+        assert_relative_eq!(weights[2], 5.0 / 12.0, epsilon = 1e-14);
+        assert_relative_eq!(weights[3], 1.0 / 12.0, epsilon = 1e-14);
+    }
+
+    #[test]
+    fn test_polyset_orthonormal() {
+        for cell_type in CELL_TYPES {
+            for degree in 0..5 {
+                let rule = make_quadrature(cell_type, 2 * degree);
+                let dim = polyset_dim(cell_type, degree);
+                let mut data = vec![0.0; rule.npoints * dim];
+                tabulate_legendre_polynomials(cell_type, &rule.points, degree, 0, &mut data);
+                for i in 0..dim {
+                    for j in 0..dim {
+                        let product: f64 = rule
+                            .weights
+                            .iter()
+                            .enumerate()
+                            .map(|(p, w)| w * data[p * dim + i] * data[p * dim + j])
+                            .sum();
+                        assert_relative_eq!(
+                            product,
+                            if i == j { 1.0 } else { 0.0 },
+                            epsilon = 1e-12
+                        );
+                    }
+                }
+            }
+        }
+    }
+}